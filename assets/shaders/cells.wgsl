@@ -0,0 +1,98 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(2) @binding(0) var<uniform> tint: vec4<f32>;
+// one texel per cell
+// channel 1 - cell state bits
+// channel 2 - adjacency
+// channel 3 - 0 untinted, 1 hovered, 2 pressed
+// channel 4 - unused
+@group(2) @binding(1) var cells: texture_2d<f32>;
+
+// every sprite packed into one atlas
+@group(2) @binding(2) var sprites: texture_2d<f32>;
+@group(2) @binding(3) var sprite_sampler: sampler;
+// uv min in xy and max in zw of each sprite in the atlas
+@group(2) @binding(4) var<uniform> sprite_rects: array<vec4<f32>, 11>;
+// multiplied over the number sprites
+@group(2) @binding(5) var<uniform> number_colors: array<vec4<f32>, 8>;
+
+// sprite_rects order
+const COVER_SPRITE: u32 = 0u;
+const FLAG_SPRITE: u32 = 1u;
+const MINE_SPRITE: u32 = 2u;
+const NUMBER_SPRITES: u32 = 3u;
+
+// sim::grid::CellState
+const MINE: u32 = 1u;
+const REVEALED: u32 = 2u;
+const NON_PLAYABLE: u32 = 4u;
+const FLAG: u32 = 8u;
+
+const HOVERED: f32 = 0.85;
+const PRESSED: f32 = 0.6;
+
+// straight alpha src over dst
+fn over(dst: vec4<f32>, src: vec4<f32>) -> vec4<f32>
+{
+	let alpha = src.a + dst.a * (1.0 - src.a);
+	if alpha <= 0.0
+	{
+		return vec4(0.0);
+	}
+	let color = (src.rgb * src.a + dst.rgb * dst.a * (1.0 - src.a)) / alpha;
+	return vec4(color, alpha);
+}
+
+// sampled at level 0, the sprite to use differs per cell so this isn't uniform control flow
+fn sprite(index: u32, uv: vec2<f32>) -> vec4<f32>
+{
+	let rect = sprite_rects[index];
+	return textureSampleLevel(sprites, sprite_sampler, mix(rect.xy, rect.zw, uv), 0.0);
+}
+
+fn adjacency(count: u32, uv: vec2<f32>) -> vec4<f32>
+{
+	let index = clamp(count, 1u, 8u) - 1u;
+	return sprite(NUMBER_SPRITES + index, uv) * number_colors[index];
+}
+
+@fragment
+fn fragment(vert: VertexOutput) -> @location(0) vec4<f32>
+{
+	let size = textureDimensions(cells);
+	let pos = vert.uv * vec2<f32>(size);
+	let cell = min(vec2<u32>(pos), size - 1u);
+	// sprites run top down, cells run bottom up
+	let uv = vec2(fract(pos.x), 1.0 - fract(pos.y));
+
+	let data = vec4<u32>(textureLoad(cells, cell, 0) * 255.0 + 0.5);
+	let state = data.r;
+
+	var color = vec4(0.0);
+	if (state & (MINE | REVEALED)) == (MINE | REVEALED)
+	{
+		color = over(color, sprite(MINE_SPRITE, uv));
+	}
+	if (state & REVEALED) != 0u && (state & (NON_PLAYABLE | MINE)) == 0u && data.g != 0u
+	{
+		color = over(color, adjacency(data.g, uv));
+	}
+	if (state & (NON_PLAYABLE | REVEALED)) == 0u
+	{
+		var cover = sprite(COVER_SPRITE, uv);
+		if data.b == 1u
+		{
+			cover = vec4(cover.rgb * HOVERED, cover.a);
+		}
+		else if data.b == 2u
+		{
+			cover = vec4(cover.rgb * PRESSED, cover.a);
+		}
+		color = over(color, cover);
+	}
+	if (state & FLAG) != 0u
+	{
+		color = over(color, sprite(FLAG_SPRITE, uv));
+	}
+	return tint * color;
+}
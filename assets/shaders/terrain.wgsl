@@ -1,6 +1,25 @@
 #import bevy_sprite::mesh2d_vertex_output::VertexOutput
 
-@group(2) @binding(0) var<uniform> tint: vec4<f32>;
+struct Terrain
+{
+	tint: vec4<f32>,
+	shallow_color: vec4<f32>,
+	deep_color: vec4<f32>,
+	foam_color: vec4<f32>,
+	// in cells
+	board_size: vec2<f32>,
+	wave_speed: vec2<f32>,
+	wave_scale: f32,
+	wave_strength: f32,
+	foam_width: f32,
+	foam_speed: f32,
+	foam_sharpness: f32,
+	shore_level: f32,
+	// seconds
+	time: f32,
+}
+
+@group(2) @binding(0) var<uniform> terrain: Terrain;
 // channel 1 - elevation
 // channel 2 - unused
 // channel 3 - unused
@@ -11,10 +30,84 @@
 @group(2) @binding(3) var color_palette: texture_2d<f32>; // bevy can't load 1d images natively, ugh
 @group(2) @binding(4) var palette_sampler: sampler;
 
+fn hash(p: vec2<f32>) -> f32
+{
+	let q = fract(p * vec2(123.34, 456.21));
+	let r = q + dot(q, q + 45.32);
+	return fract(r.x * r.y);
+}
+
+// smooth value noise in [0, 1]
+fn noise(p: vec2<f32>) -> f32
+{
+	let i = floor(p);
+	let f = fract(p);
+	let u = f * f * (3.0 - 2.0 * f);
+	let a = hash(i);
+	let b = hash(i + vec2(1.0, 0.0));
+	let c = hash(i + vec2(0.0, 1.0));
+	let d = hash(i + vec2(1.0, 1.0));
+	return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// two layers scrolling against each other so the pattern never just slides
+fn waves(p: vec2<f32>) -> f32
+{
+	let t = terrain.time;
+	let a = noise(p + terrain.wave_speed * t);
+	let b = noise(p * 1.7 - terrain.wave_speed.yx * t * 1.3 + vec2(17.0, 9.0));
+	return a * 0.6 + b * 0.4;
+}
+
+fn elevation_at(uv: vec2<f32>) -> f32
+{
+	return textureSampleLevel(elevation_texture, elevation_sampler, uv, 0.0).r;
+}
+
 @fragment
 fn fragment(vert: VertexOutput) -> @location(0) vec4<f32>
 {
-	let elevation = textureSample(elevation_texture, elevation_sampler, vert.uv).r;
-	let color = textureSample(color_palette, palette_sampler, vec2(elevation, 0.0));
-    return tint * color;
+	// texture samples stay in uniform control flow
+	let texel = 1.0 / vec2<f32>(textureDimensions(elevation_texture));
+	let elevation = elevation_at(vert.uv);
+	let slope = vec2
+	(
+		elevation_at(vert.uv + vec2(texel.x, 0.0)) - elevation_at(vert.uv - vec2(texel.x, 0.0)),
+		elevation_at(vert.uv + vec2(0.0, texel.y)) - elevation_at(vert.uv - vec2(0.0, texel.y)),
+	);
+	let land = textureSample(color_palette, palette_sampler, vec2(elevation, 0.0));
+
+	let cell = vert.uv * terrain.board_size;
+	let p = cell * terrain.wave_scale;
+
+	// surface normal from the wave heights
+	let e = 0.05;
+	let h = waves(p);
+	let normal = normalize(vec3
+	(
+		(h - waves(p + vec2(e, 0.0))) * terrain.wave_strength / e,
+		(h - waves(p + vec2(0.0, e))) * terrain.wave_strength / e,
+		1.0,
+	));
+	let light = normalize(vec3(-0.4, 0.5, 0.75));
+	let diffuse = 0.85 + 0.15 * dot(normal, light);
+	let specular = pow(max(dot(reflect(-light, normal), vec3(0.0, 0.0, 1.0)), 0.0), 24.0) * 0.35;
+
+	// 0 in open water, 1 at the shore
+	let depth = clamp(elevation / max(terrain.shore_level, 0.0001), 0.0, 1.0);
+	var water = mix(terrain.deep_color.rgb, terrain.shallow_color.rgb, depth * depth);
+	water = water * diffuse + vec3(specular);
+
+	// foam in a band below the shore, only where the land rises steeply, pulsing outwards
+	let steepness = clamp(length(slope) * terrain.foam_sharpness, 0.0, 1.0);
+	let band = smoothstep(terrain.shore_level - terrain.foam_width, terrain.shore_level, elevation);
+	let pulse = 0.5 + 0.5 * sin(elevation / max(terrain.foam_width, 0.0001) * 12.0 - terrain.time * terrain.foam_speed);
+	let foam = band * steepness * smoothstep(0.35, 0.75, pulse * 0.6 + noise(cell * 2.0 + h) * 0.6) * terrain.foam_color.a;
+	water = mix(water, terrain.foam_color.rgb, foam);
+
+	// a thin soft edge where the sea meets the land
+	let edge = fwidth(elevation);
+	let shore = smoothstep(terrain.shore_level - edge, terrain.shore_level + edge, elevation);
+	let color = mix(vec4(water, 1.0), land, shore);
+	return terrain.tint * color;
 }
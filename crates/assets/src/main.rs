@@ -1,13 +1,24 @@
 
+mod validate;
+
 use bevy::asset::LoadedFolder;
 use bevy::image::ImageSampler;
 use bevy::prelude::*;
 use bevy::render::settings::WgpuSettings;
 use bevy::render::RenderPlugin;
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::winit::WinitPlugin;
 
 use base::assets::find_folder;
 use base::assets::ASSETS_FOLDER;
 use base::assets::ROOT_FOLDER;
+use bevyx::atlas::AtlasManifest;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use strum::IntoEnumIterator;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States)]
 enum AppState
@@ -17,12 +28,36 @@ enum AppState
     Finish,
 }
 
-#[derive(Component, Default)]
-struct AtlasFolder(Handle<LoadedFolder>);
+// source folder under root, name of the atlas written to assets/atlases
+const ATLASES: &[(&str, &str)] =
+&[
+    ("textures/gameplay/", "gameplay"),
+    ("textures/cells/", "cells"),
+];
+const ATLAS_FOLDER: &str = "atlases";
+// keeps linear filtering from bleeding between sprites
+const ATLAS_PADDING: UVec2 = UVec2::splat(2);
+
+#[derive(Component)]
+struct AtlasFolder
+{
+    folder: Handle<LoadedFolder>,
+    name: &'static str,
+}
+
+#[derive(Resource)]
+struct OutputFolder(PathBuf);
 
 fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>)
 {
-    commands.spawn(AtlasFolder(asset_server.load_folder("textures/gameplay/")));
+    for (folder, name) in ATLASES
+    {
+        commands.spawn(AtlasFolder
+        {
+            folder: asset_server.load_folder(*folder),
+            name,
+        });
+    }
 }
 
 fn check_assets
@@ -38,7 +73,7 @@ fn check_assets
     for atlas_folder in atlas_folders
     {
         total += 1;
-        if asset_server.is_loaded_with_dependencies(&atlas_folder.0)
+        if asset_server.is_loaded_with_dependencies(&atlas_folder.folder)
         {
             loaded += 1;
         }
@@ -65,24 +100,75 @@ fn process_atlases
     atlas_folders: Query<&AtlasFolder>,
     loaded_folders: Res<Assets<LoadedFolder>>,
     mut textures: ResMut<Assets<Image>>,
+    output: Res<OutputFolder>,
+    mut exit: EventWriter<AppExit>,
 )
 {
     for atlas_folder in atlas_folders
     {
-        let Some(folder) = loaded_folders.get(&atlas_folder.0) else
+        let Some(folder) = loaded_folders.get(&atlas_folder.folder) else
         {
             continue;
         };
 
-        let (_texture_atlas_linear, _linear_sources, _linear_texture) = create_texture_atlas(
+        let (layout, sources, texture) = create_texture_atlas(
             folder,
-            None,
+            Some(ATLAS_PADDING),
             Some(ImageSampler::linear()),
             &mut textures,
         );
+
+        // sprites are named by file, without the extension
+        let names = folder.handles
+            .iter()
+            .filter_map(|handle|
+            {
+                let name = handle.path()?.path().file_stem()?.to_string_lossy().into_owned();
+                let index = sources.texture_index(handle.id().typed_unchecked::<Image>())?;
+                Some((name, index))
+            })
+            .collect();
+
+        match write_atlas(&output.0, atlas_folder.name, layout, names, textures.get(&texture).unwrap())
+        {
+            Ok(manifest_path) => println!("Wrote {}", manifest_path.display()),
+            Err(err) =>
+            {
+                eprintln!("Failed to write atlas {} with error [{}]", atlas_folder.name, err);
+                // wins over the success do_exit sends
+                exit.write(AppExit::error());
+            }
+        }
     }
 }
 
+// the packed png and the manifest bevyx::atlas loads it by
+fn write_atlas
+(
+    folder: &Path,
+    name: &str,
+    layout: TextureAtlasLayout,
+    names: BTreeMap<String, usize>,
+    image: &Image,
+) -> Result<PathBuf, Box<dyn std::error::Error>>
+{
+    std::fs::create_dir_all(folder)?;
+
+    let image_name = format!("{name}.png");
+    image.clone().try_into_dynamic()?.save(folder.join(&image_name))?;
+
+    let manifest = AtlasManifest
+    {
+        image: image_name.into(),
+        layout,
+        names,
+    };
+    let manifest_path = folder.join(format!("{name}.{}", bevyx::atlas::EXTENSION));
+    let pretty = ron::ser::PrettyConfig::default();
+    std::fs::write(&manifest_path, ron::ser::to_string_pretty(&manifest, pretty)?)?;
+    Ok(manifest_path)
+}
+
 fn create_texture_atlas
 (
     folder: &LoadedFolder,
@@ -120,16 +206,24 @@ fn create_texture_atlas
     (texture_atlas_layout, texture_atlas_sources, texture)
 }
 
-fn main()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display, strum::EnumIter)]
+#[strum(serialize_all = "lowercase")]
+enum Command
 {
-    let assets_folder = find_folder(ASSETS_FOLDER).expect("Failed to find ASSETS_FOLDER");
-    let root_folder = find_folder(ROOT_FOLDER).expect("Failed to find ROOT_FOLDER");
-    println!("assets folder located at {}", assets_folder.display());
-    println!("root folder located at {}", root_folder.display());
-
-    base::hello_base();
-    bevyx::hello_bevyx();
+    // pack root/textures folders into atlases
+    Atlas,
+    // parse every RON file as its type and check the paths in it exist
+    Validate,
+    // overwrite the tuning files with the code's defaults
+    Defaults,
+    // list assets nothing points at
+    Unused,
+    // atlas, validate and unused, the default
+    All,
+}
 
+fn pack_atlases(root_folder: &Path, assets_folder: &Path) -> AppExit
+{
     App::new()
         .add_plugins
         (
@@ -157,12 +251,87 @@ fn main()
                      primary_window: None,
                      ..default()
                  })
+                // no window, so no event loop to run in either
+                .disable::<WinitPlugin>()
         )
+        .add_plugins(ScheduleRunnerPlugin::default())
+        .insert_resource(OutputFolder(assets_folder.join(ATLAS_FOLDER)))
         .init_state::<AppState>()
         .add_systems(OnEnter(AppState::LoadAssets), load_assets)
         .add_systems(Update, check_assets.run_if(in_state(AppState::LoadAssets)))
         .add_systems(OnEnter(AppState::Finish), process_atlases)
         .add_systems(Update, do_exit.run_if(in_state(AppState::Finish)))
-        .run();
+        .run()
+}
+
+// errors fail the run, unused assets are only reported
+fn validate(assets_folder: &Path, unused: bool) -> bool
+{
+    let report = validate::validate(assets_folder);
+    for err in &report.errors
+    {
+        eprintln!("error: {err}");
+    }
+    println!("Validated with {} errors", report.errors.len());
+
+    if unused
+    {
+        // paths written out in the code count as used, the crates sit next to the root folder
+        let unused = validate::unused(assets_folder, Path::new("crates"), &report.referenced);
+        for file in &unused
+        {
+            println!("unused: {}", file.display());
+        }
+        println!("{} unused assets", unused.len());
+    }
+
+    report.errors.is_empty()
 }
 
+fn main() -> ExitCode
+{
+    let command = match std::env::args().nth(1)
+    {
+        Some(arg) => match arg.parse::<Command>()
+        {
+            Ok(command) => command,
+            Err(_) =>
+            {
+                let commands: Vec<String> = Command::iter().map(|command| command.to_string()).collect();
+                eprintln!("Unknown command {arg}, expected one of {}", commands.join(", "));
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Command::All,
+    };
+
+    let assets_folder = find_folder(ASSETS_FOLDER).expect("Failed to find ASSETS_FOLDER");
+    let root_folder = find_folder(ROOT_FOLDER).expect("Failed to find ROOT_FOLDER");
+    println!("assets folder located at {}", assets_folder.display());
+    println!("root folder located at {}", root_folder.display());
+
+    base::hello_base();
+    bevyx::hello_bevyx();
+
+    let ok = match command
+    {
+        Command::Atlas => pack_atlases(&root_folder, &assets_folder).is_success(),
+        Command::Validate => validate(&assets_folder, false),
+        Command::Defaults =>
+        {
+            validate::write_defaults();
+            true
+        }
+        Command::Unused => validate(&assets_folder, true),
+        Command::All => pack_atlases(&root_folder, &assets_folder).is_success() & validate(&assets_folder, true),
+    };
+
+    if ok
+    {
+        ExitCode::SUCCESS
+    }
+    else
+    {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,258 @@
+use base::tuning::Tuning;
+use bevyx::atlas::AtlasManifest;
+use sim::puzzle::PuzzlePack;
+use sup::actions::InputMap;
+use sup::config::GameConfig;
+use vis::board_vis_tuning::BoardVisTuning;
+use vis::board_vis_tuning::TerrainTuning;
+use vis::theme::BoardTheme;
+use vis::theme::ThemeSelection;
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ValidateError
+{
+    #[error("{0}: could not read: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("{0}: {1}")]
+    Ron(PathBuf, ron::error::SpannedError),
+    #[error("{0}: points at {1}, which doesn't exist")]
+    MissingReference(PathBuf, PathBuf),
+    #[error("{0}: no type is known for this file")]
+    UnknownFile(PathBuf),
+}
+
+// player saves live in the assets folder too, they aren't assets
+const SKIPPED: &[&str] = &["save"];
+
+// asset paths a file points at
+trait References
+{
+    fn references(&self, file: &Path) -> Vec<PathBuf>;
+}
+
+impl References for BoardVisTuning
+{
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        vec![]
+    }
+}
+
+impl References for TerrainTuning
+{
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        vec![]
+    }
+}
+
+impl References for ThemeSelection
+{
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        vec![self.theme.clone()]
+    }
+}
+
+impl References for BoardTheme
+{
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        self.files().map(Path::to_path_buf).collect()
+    }
+}
+
+impl References for AtlasManifest
+{
+    fn references(&self, file: &Path) -> Vec<PathBuf>
+    {
+        // the image is relative to the manifest
+        vec![file.parent().unwrap_or(Path::new("")).join(&self.image)]
+    }
+}
+
+impl References for PuzzlePack
+{
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        vec![]
+    }
+}
+
+impl References for InputMap
+{
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        vec![]
+    }
+}
+
+impl References for GameConfig
+{
+    // a board file is optional, it only has to exist when set
+    fn references(&self, _file: &Path) -> Vec<PathBuf>
+    {
+        self.board_file.iter().cloned().collect()
+    }
+}
+
+type Check = fn(&Path, &Path) -> Result<Vec<PathBuf>, ValidateError>;
+
+fn check<T>(assets: &Path, file: &Path) -> Result<Vec<PathBuf>, ValidateError>
+    where T: References + for<'de> serde::Deserialize<'de>
+{
+    let bytes = std::fs::read(assets.join(file)).map_err(|err| ValidateError::Io(file.to_path_buf(), err))?;
+    let value: T = ron::de::from_bytes(&bytes).map_err(|err| ValidateError::Ron(file.to_path_buf(), err))?;
+    Ok(value.references(file))
+}
+
+// the type each RON file in the assets folder has to parse as, by path then by suffix
+fn check_for(file: &Path) -> Option<Check>
+{
+    let exact: &[(&str, Check)] =
+    &[
+        ("tuning/board_vis.ron", check::<BoardVisTuning>),
+        ("tuning/terrain.ron", check::<TerrainTuning>),
+        ("tuning/theme.ron", check::<ThemeSelection>),
+        ("tuning/input.ron", check::<InputMap>),
+        ("tuning/game.ron", check::<GameConfig>),
+    ];
+    let suffix: &[(&str, &str, Check)] =
+    &[
+        ("themes/", ".ron", check::<BoardTheme>),
+        ("atlases/", ".atlas.ron", check::<AtlasManifest>),
+        ("puzzles/", ".puzzles.ron", check::<PuzzlePack>),
+    ];
+
+    let name = file.to_string_lossy().replace('\\', "/");
+    exact.iter()
+        .find(|(path, _)| name == *path)
+        .map(|(_, check)| *check)
+        .or_else(|| suffix.iter().find(|(folder, end, _)| name.starts_with(folder) && name.ends_with(end)).map(|(_, _, check)| *check))
+}
+
+// every file under the folder, relative to it
+fn files(folder: &Path) -> Vec<PathBuf>
+{
+    fn walk(folder: &Path, relative: &Path, out: &mut Vec<PathBuf>)
+    {
+        let Ok(entries) = std::fs::read_dir(folder.join(relative)) else
+        {
+            return;
+        };
+        for entry in entries.filter_map(|entry| entry.ok())
+        {
+            let path = relative.join(entry.file_name());
+            if entry.file_type().is_ok_and(|kind| kind.is_dir())
+            {
+                walk(folder, &path, out);
+            }
+            else
+            {
+                out.push(path);
+            }
+        }
+    }
+
+    let mut out = vec![];
+    walk(folder, Path::new(""), &mut out);
+    out.sort();
+    out
+}
+
+fn asset_files(assets: &Path) -> impl Iterator<Item = PathBuf>
+{
+    files(assets)
+        .into_iter()
+        .filter(|file| !SKIPPED.iter().any(|skipped| file.starts_with(skipped)))
+}
+
+#[derive(Debug, Default)]
+pub struct Report
+{
+    pub errors: Vec<ValidateError>,
+    // every asset path some data file points at
+    pub referenced: BTreeSet<PathBuf>,
+}
+
+// parses every RON file as its type and checks what it points at exists
+pub fn validate(assets: &Path) -> Report
+{
+    let mut report = Report::default();
+    for file in asset_files(assets).filter(|file| file.extension().is_some_and(|extension| extension == "ron"))
+    {
+        let Some(check) = check_for(&file) else
+        {
+            report.errors.push(ValidateError::UnknownFile(file));
+            continue;
+        };
+
+        match check(assets, &file)
+        {
+            Ok(references) =>
+            {
+                for reference in references
+                {
+                    if !assets.join(&reference).is_file()
+                    {
+                        report.errors.push(ValidateError::MissingReference(file.clone(), reference.clone()));
+                    }
+                    report.referenced.insert(reference);
+                }
+            }
+            Err(err) => report.errors.push(err),
+        }
+    }
+    report
+}
+
+// overwrites the tuning files with what the code defaults to
+pub fn write_defaults()
+{
+    BoardVisTuning::default().save();
+    TerrainTuning::default().save();
+    ThemeSelection::default().save();
+    InputMap::default().save();
+    GameConfig::default().save();
+    if let Err(err) = base::ronx::write_sync(&BoardTheme::default(), &ThemeSelection::default().theme)
+    {
+        eprintln!("Failed to write the default theme with error [{}]", err);
+    }
+}
+
+// assets nothing points at, neither a data file nor a path written out in the code
+pub fn unused(assets: &Path, sources: &Path, referenced: &BTreeSet<PathBuf>) -> Vec<PathBuf>
+{
+    let code: String = files(sources)
+        .into_iter()
+        .filter(|file| file.extension().is_some_and(|extension| extension == "rs"))
+        .filter_map(|file| std::fs::read_to_string(sources.join(file)).ok())
+        .collect();
+
+    asset_files(assets)
+        .filter(|file| !referenced.contains(file))
+        // the game lists the themes folder rather than naming them
+        .filter(|file| !file.starts_with("themes"))
+        .filter(|file| !code.contains(&*file.to_string_lossy().replace('\\', "/")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_assets_are_valid()
+    {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(base::assets::ASSETS_FOLDER);
+        let report = validate(&assets);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.referenced.contains(Path::new("atlases/cells.png")));
+    }
+}
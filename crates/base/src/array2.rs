@@ -6,7 +6,7 @@ use std::fmt::Formatter;
 use std::ops::Index;
 use std::ops::IndexMut;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Array2<T>
 {
     array: Vec<T>,
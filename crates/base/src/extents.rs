@@ -27,7 +27,7 @@ bitflags!
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Extents
 {
     pub width: i32,
@@ -119,12 +119,22 @@ impl Extents
         &self,
         pos: Point,
     ) -> impl DoubleEndedIterator<Item = Point> + Clone + use<FLAGS>
+    {
+        self.neighbours_of(pos, Neighbours::from_bits_retain( FLAGS ))
+    }
+
+    // runtime version of neighbours, for when the neighbourhood is data driven
+    pub fn neighbours_of(
+        &self,
+        pos: Point,
+        flags: Neighbours,
+    ) -> impl DoubleEndedIterator<Item = Point> + Clone + use<>
     {
         let mut neigh = ArrayVec::<Point, 8>::new();
 
         let can_add = |neighbour_position: Neighbours| -> bool
         {
-            Neighbours::from_bits_retain( FLAGS ) & neighbour_position != Neighbours::None
+            flags & neighbour_position != Neighbours::None
         };
 
         let mut try_add = |pos: Point|
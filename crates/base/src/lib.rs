@@ -5,6 +5,7 @@ pub mod assets;
 pub mod debug;
 pub mod extents;
 pub mod ronx;
+pub mod save;
 pub mod tuning;
 pub mod const_default;
 pub mod random;
@@ -1,6 +1,7 @@
 
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(from = "(i32, i32)", into = "(i32, i32)")]
 pub struct Point(glam::IVec2);
 
 impl std::ops::Deref for Point
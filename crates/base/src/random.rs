@@ -1,4 +1,5 @@
 use std::num::Wrapping;
+use std::ops::Range;
 
 pub trait RandomExtractor
 {
@@ -11,6 +12,12 @@ pub trait RandomUnsafeExtractor
 	unsafe fn extract(rand: &mut RandomGenerator) -> Self;
 }
 
+// uniform over a half open range, the result only depends on the generator and the range
+pub trait RandomRange: PartialOrd + Sized
+{
+	fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self;
+}
+
 impl RandomExtractor for bool { fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() & 1 != 0 } }
 
 impl RandomExtractor for u8 { fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self } }
@@ -22,21 +29,21 @@ impl RandomExtractor for i32 { fn extract(rand: &mut RandomGenerator) -> Self {
 impl RandomExtractor for u64 { fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self } }
 impl RandomExtractor for i64 { fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self } }
 
-impl RandomExtractor for u128 { fn extract(rand: &mut RandomGenerator) -> Self { (rand.generate() as Self) << 64 + rand.generate() as Self } }
-impl RandomExtractor for i128 { fn extract(rand: &mut RandomGenerator) -> Self { (rand.generate() as Self) << 64 + rand.generate() as Self } }
+impl RandomExtractor for u128 { fn extract(rand: &mut RandomGenerator) -> Self { ((rand.generate() as Self) << 64) | rand.generate() as Self } }
+impl RandomExtractor for i128 { fn extract(rand: &mut RandomGenerator) -> Self { u128::extract(rand) as Self } }
+
+// in [0, 1), built from the top bits so every value is exact and the same everywhere
+impl RandomExtractor for f32 { fn extract(rand: &mut RandomGenerator) -> Self { (rand.generate() >> 40) as Self / (1u64 << 24) as Self } }
+impl RandomExtractor for f64 { fn extract(rand: &mut RandomGenerator) -> Self { (rand.generate() >> 11) as Self / (1u64 << 53) as Self } }
 
 impl RandomUnsafeExtractor for usize { unsafe fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self } }
 impl RandomUnsafeExtractor for isize { unsafe fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self } }
 
-// TODO test range is within -1 and 1
-impl RandomUnsafeExtractor for f32 { unsafe fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self / u64::MAX as Self } }
-impl RandomUnsafeExtractor for f64 { unsafe fn extract(rand: &mut RandomGenerator) -> Self { rand.generate() as Self / u64::MAX as Self } }
-
 impl RandomUnsafeExtractor for glam::Vec2
 {
 	unsafe fn extract(rand: &mut RandomGenerator) -> Self
 	{
-		unsafe { glam::Vec2::new(f32::extract(rand), f32::extract(rand)).normalize() }
+		glam::Vec2::new(rand.random(), rand.random()).normalize()
 	}
 }
 
@@ -44,10 +51,26 @@ impl RandomUnsafeExtractor for glam::Vec3
 {
 	unsafe fn extract(rand: &mut RandomGenerator) -> Self
 	{
-		unsafe { glam::Vec3::new(f32::extract(rand), f32::extract(rand), f32::extract(rand)).normalize() }
+		glam::Vec3::new(rand.random(), rand.random(), rand.random()).normalize()
 	}
 }
 
+// signed ranges are offset from their start, the span always fits in the unsigned type of the same width
+impl RandomRange for u8 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start + rand.below((range.end - range.start) as u64) as Self } }
+impl RandomRange for i8 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start.wrapping_add(rand.below(range.end.wrapping_sub(range.start) as u8 as u64) as Self) } }
+impl RandomRange for u16 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start + rand.below((range.end - range.start) as u64) as Self } }
+impl RandomRange for i16 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start.wrapping_add(rand.below(range.end.wrapping_sub(range.start) as u16 as u64) as Self) } }
+impl RandomRange for u32 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start + rand.below((range.end - range.start) as u64) as Self } }
+impl RandomRange for i32 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start.wrapping_add(rand.below(range.end.wrapping_sub(range.start) as u32 as u64) as Self) } }
+impl RandomRange for u64 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start + rand.below(range.end - range.start) } }
+impl RandomRange for i64 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start.wrapping_add(rand.below(range.end.wrapping_sub(range.start) as u64) as Self) } }
+impl RandomRange for usize { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start + rand.below((range.end - range.start) as u64) as Self } }
+impl RandomRange for isize { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { range.start.wrapping_add(rand.below(range.end.wrapping_sub(range.start) as usize as u64) as Self) } }
+
+// rounding can land on the end when the range is much smaller than its start, that comes back to the value below it
+impl RandomRange for f32 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { (range.start + rand.random::<Self>() * (range.end - range.start)).min(range.end.next_down()) } }
+impl RandomRange for f64 { fn extract_range(rand: &mut RandomGenerator, range: Range<Self>) -> Self { (range.start + rand.random::<Self>() * (range.end - range.start)).min(range.end.next_down()) } }
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct RandomGenerator
 {
@@ -59,12 +82,22 @@ impl RandomGenerator
 	pub fn new(seed: u64) -> Self
 	{
 		assert!(seed != 0);
-		let mut state = Wrapping(seed);
+		Self::seeded(Wrapping(seed))
+	}
+
+	fn seeded(mut state: Wrapping::<u64>) -> Self
+	{
 		Self
 		{
 			state: [Self::splitmix(&mut state), Self::splitmix(&mut state)]
 		}
-		
+	}
+
+	// hashes a value through splitmix, useful for deriving a seed from other data
+	pub fn hash(value: u64) -> u64
+	{
+		let mut state = Wrapping(value);
+		Self::splitmix(&mut state).0
 	}
 
 	// xshiro splitmix64
@@ -92,6 +125,21 @@ impl RandomGenerator
 		return x.0;
 	}
 
+	// lemire's multiply and reject, unbiased where a modulo favours the low values
+	fn below(&mut self, bound: u64) -> u64
+	{
+		let mut product = self.generate() as u128 * bound as u128;
+		if (product as u64) < bound
+		{
+			let threshold = bound.wrapping_neg() % bound;
+			while (product as u64) < threshold
+			{
+				product = self.generate() as u128 * bound as u128;
+			}
+		}
+		(product >> 64) as u64
+	}
+
 	pub fn random<T>(&mut self) -> T
 		where T: RandomExtractor
 	{
@@ -104,19 +152,93 @@ impl RandomGenerator
 		unsafe { T::extract(self) }
 	}
 
+	pub fn range<T>(&mut self, range: Range<T>) -> T
+		where T: RandomRange
+	{
+		assert!(range.start < range.end, "empty range");
+		T::extract_range(self, range)
+	}
+
+	// an index picked in proportion to its weight, None when every weight is zero
+	pub fn weighted(&mut self, weights: &[u32]) -> Option<usize>
+	{
+		let total = weights.iter().map(|weight| *weight as u64).sum::<u64>();
+		if total == 0
+		{
+			return None;
+		}
+
+		let mut pick = self.below(total);
+		for (i, weight) in weights.iter().enumerate()
+		{
+			if pick < *weight as u64
+			{
+				return Some(i);
+			}
+			pick -= *weight as u64;
+		}
+		unreachable!()
+	}
+
 	pub fn shuffle<T>(&mut self, container: &mut [T])
-		where T: Copy
 	{
 		// fisher-yates shuffle
 		for i in (1..container.len()).rev()
 		{
-			let j = self.random::<u32>() as usize % (i + 1);
-			let tmp = container[i];
-			container[i] = container[j];
-			container[j] = tmp;
+			let j = self.below(i as u64 + 1) as usize;
+			container.swap(i, j);
 		}
 		
 	}
+
+	// moves a uniform pick of count elements to the front and returns them, the rest are left in any order
+	pub fn partial_shuffle<'a, T>(&mut self, container: &'a mut [T], count: usize) -> &'a mut [T]
+	{
+		let count = count.min(container.len());
+		for i in 0..count
+		{
+			let j = i + self.below((container.len() - i) as u64) as usize;
+			container.swap(i, j);
+		}
+		&mut container[..count]
+	}
+
+	// reservoir sampling, count items from an iterator of unknown length with one pass
+	pub fn sample<I>(&mut self, items: I, count: usize) -> Vec<I::Item>
+		where I: IntoIterator
+	{
+		let mut reservoir = Vec::with_capacity(count);
+		for (i, item) in items.into_iter().enumerate()
+		{
+			if i < count
+			{
+				reservoir.push(item);
+				continue;
+			}
+
+			let j = self.below(i as u64 + 1) as usize;
+			if j < count
+			{
+				reservoir[j] = item;
+			}
+		}
+		reservoir
+	}
+
+	// xorshiftr+ has no jump polynomial since its state goes through an addition,
+	// so independent streams are seeded through splitmix instead
+
+	// a new generator seeded from this one, which moves on by one value
+	pub fn split(&mut self) -> Self
+	{
+		Self::seeded(Wrapping(self.generate()))
+	}
+
+	// the numbered stream of this generator, without advancing it
+	pub fn stream(&self, index: u64) -> Self
+	{
+		Self::seeded(Wrapping(Self::hash(self.state[0].0 ^ Self::hash(self.state[1].0 ^ index))))
+	}
 }
 
 #[cfg(test)]
@@ -124,6 +246,17 @@ mod tests
 {
 	use super::*;
 
+	const SAMPLES: u32 = 100_000;
+
+	// values past these are a one in a thousand chance for a uniform source
+	const CHI_SQUARED_5: f64 = 20.52;
+	const CHI_SQUARED_9: f64 = 27.88;
+
+	fn chi_squared(counts: &[u32], expected: f64) -> f64
+	{
+		counts.iter().map(|count| (*count as f64 - expected).powi(2) / expected).sum()
+	}
+
 	#[test]
 	fn test_deterministic()
 	{
@@ -131,4 +264,164 @@ mod tests
 		let b = RandomGenerator::new(1).random::<u64>();
 		assert_eq!(a, b);
 	}
+
+	#[test]
+	fn test_u128_halves()
+	{
+		let mut rand = RandomGenerator::new(7);
+		let mut copy = rand.clone();
+		let value = rand.random::<u128>();
+		assert_eq!((value >> 64) as u64, copy.generate());
+		assert_eq!(value as u64, copy.generate());
+	}
+
+	#[test]
+	fn test_range()
+	{
+		let mut rand = RandomGenerator::new(3);
+		let mut counts = [0u32; 10];
+		for _ in 0..SAMPLES
+		{
+			counts[rand.range(0..10usize)] += 1;
+		}
+		assert!(chi_squared(&counts, SAMPLES as f64 / 10.0) < CHI_SQUARED_9, "{counts:?}");
+
+		for _ in 0..1000
+		{
+			assert!((-5..5).contains(&rand.range(-5i8..5)));
+			assert!((i32::MIN..-7).contains(&rand.range(i32::MIN..-7)));
+			assert_eq!(rand.range(9u64..10), 9);
+		}
+		rand.range(i64::MIN..i64::MAX);
+
+		// a modulo would land in the lower half two thirds of the time
+		let bound = u64::MAX / 3 * 2;
+		let lower = (0..SAMPLES).filter(|_| rand.range(0..bound) < bound / 2).count() as f64 / SAMPLES as f64;
+		assert!((lower - 0.5).abs() < 0.01, "{lower}");
+	}
+
+	#[test]
+	fn test_floats()
+	{
+		let mut rand = RandomGenerator::new(5);
+		let mut counts = [0u32; 10];
+		let mut sum = 0.0;
+		for _ in 0..SAMPLES
+		{
+			let value = rand.random::<f64>();
+			assert!((0.0..1.0).contains(&value));
+			counts[(value * 10.0) as usize] += 1;
+			sum += value;
+
+			assert!((0.0..1.0).contains(&rand.random::<f32>()));
+		}
+		assert!(chi_squared(&counts, SAMPLES as f64 / 10.0) < CHI_SQUARED_9, "{counts:?}");
+		assert!((sum / SAMPLES as f64 - 0.5).abs() < 0.005);
+
+		// the steps here are as wide as the range, so rounding up would hit the end
+		for _ in 0..1000
+		{
+			let value = rand.range(100_000_000.0f32..100_000_008.0);
+			assert!((100_000_000.0..100_000_008.0).contains(&value), "{value}");
+			let value = rand.range(1e16f64..1e16 + 2.0);
+			assert!((1e16..1e16 + 2.0).contains(&value), "{value}");
+		}
+
+		// the same bits on every platform
+		let mut rand = RandomGenerator::new(5);
+		let bits = rand.clone().generate();
+		assert_eq!(rand.random::<f64>(), (bits >> 11) as f64 * 2f64.powi(-53));
+	}
+
+	#[test]
+	fn test_weighted()
+	{
+		let mut rand = RandomGenerator::new(11);
+		let mut counts = [0u32; 3];
+		for _ in 0..SAMPLES
+		{
+			counts[rand.weighted(&[1, 0, 3]).unwrap()] += 1;
+		}
+		assert_eq!(counts[1], 0);
+		assert!((counts[2] as f64 / counts[0] as f64 - 3.0).abs() < 0.1, "{counts:?}");
+
+		assert_eq!(rand.weighted(&[]), None);
+		assert_eq!(rand.weighted(&[0, 0]), None);
+		assert_eq!(rand.weighted(&[0, 5]), Some(1));
+	}
+
+	#[test]
+	fn test_shuffle()
+	{
+		let mut rand = RandomGenerator::new(13);
+		let permutations = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+		let mut counts = [0u32; 6];
+		for _ in 0..SAMPLES
+		{
+			let mut values = [0, 1, 2];
+			rand.shuffle(&mut values);
+			counts[permutations.iter().position(|permutation| *permutation == values).unwrap()] += 1;
+		}
+		assert!(chi_squared(&counts, SAMPLES as f64 / 6.0) < CHI_SQUARED_5, "{counts:?}");
+	}
+
+	#[test]
+	fn test_partial_shuffle()
+	{
+		let mut rand = RandomGenerator::new(17);
+		let mut counts = [0u32; 10];
+		for _ in 0..SAMPLES
+		{
+			let mut values: Vec<usize> = (0..10).collect();
+			for value in rand.partial_shuffle(&mut values, 3)
+			{
+				counts[*value] += 1;
+			}
+			values.sort();
+			assert!(values.iter().copied().eq(0..10));
+		}
+		assert!(chi_squared(&counts, SAMPLES as f64 * 0.3) < CHI_SQUARED_9, "{counts:?}");
+
+		let mut values = [1, 2];
+		assert_eq!(rand.partial_shuffle(&mut values, 5).len(), 2);
+	}
+
+	#[test]
+	fn test_sample()
+	{
+		let mut rand = RandomGenerator::new(19);
+		let mut counts = [0u32; 10];
+		for _ in 0..SAMPLES
+		{
+			let sample = rand.sample(0..10usize, 3);
+			assert_eq!(sample.len(), 3);
+			for value in sample
+			{
+				counts[value] += 1;
+			}
+		}
+		assert!(chi_squared(&counts, SAMPLES as f64 * 0.3) < CHI_SQUARED_9, "{counts:?}");
+
+		assert_eq!(rand.sample(0..2, 5), vec![0, 1]);
+	}
+
+	#[test]
+	fn test_streams()
+	{
+		let mut rand = RandomGenerator::new(23);
+		let streams = [rand.split(), rand.split(), rand.stream(0), rand.stream(1)];
+		assert_eq!(rand.stream(1), rand.stream(1));
+
+		// every pair should share about half their bits
+		for (i, a) in streams.iter().enumerate()
+		{
+			for b in &streams[i + 1..]
+			{
+				let (mut a, mut b) = (a.clone(), b.clone());
+				let same = (0..SAMPLES / 64).map(|_| (!(a.generate() ^ b.generate())).count_ones()).sum::<u32>();
+				let fraction = same as f64 / (SAMPLES / 64 * 64) as f64;
+				assert!((fraction - 0.5).abs() < 0.01, "{fraction}");
+			}
+		}
+	}
 }
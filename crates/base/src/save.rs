@@ -0,0 +1,40 @@
+
+use crate::debug_name;
+
+// player data written at runtime, unlike tuning a missing file is expected on first launch
+pub trait SaveData
+{
+    fn path() -> &'static std::path::Path;
+
+    fn load() -> Self where Self: Sized + Default, for<'de> Self: serde::Deserialize<'de>
+    {
+        match crate::ronx::read_sync(Self::path())
+        {
+            Ok(data) =>
+            {
+                data
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Self::default()
+            },
+            Err(err) =>
+            {
+                eprintln!("{} -- Failed to load [{}] with error [{}]", debug_name!(), Self::path().display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) where Self: Sized + serde::Serialize
+    {
+        let full_path = std::path::Path::new(crate::assets::ASSETS_FOLDER).join(Self::path());
+        let result = full_path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| crate::ronx::write_sync(&self, Self::path()));
+        if let Err(err) = result
+        {
+            eprintln!("{} -- Failed to write [{}] with error [{}]", debug_name!(), Self::path().display(), err);
+        }
+    }
+}
@@ -5,9 +5,14 @@ pub trait Tuning
 {
     fn path() -> &'static std::path::Path;
 
+    fn try_load() -> std::io::Result<Self> where Self: Sized, for<'de> Self: serde::Deserialize<'de>
+    {
+        crate::ronx::read_sync(Self::path())
+    }
+
     fn load() -> Self where Self: Sized + Default, for<'de> Self: serde::Deserialize<'de>
     {
-        match crate::ronx::read_sync(Self::path())
+        match Self::try_load()
         {
             Ok(tuning) =>
             {
@@ -1 +1,117 @@
+use bevy::app::{App, Plugin};
+use bevy::asset::io::Reader;
+use bevy::asset::{Asset, AssetApp, AssetLoader, Handle, LoadContext};
+use bevy::image::{Image, TextureAtlasLayout};
+use bevy::math::{Rect, Vec2};
+use bevy::platform::collections::HashMap;
+use bevy::reflect::TypePath;
+use ron::de::from_bytes;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use thiserror::Error;
 
+pub const EXTENSION: &str = "atlas.ron";
+
+// what the assets tool writes next to the packed image
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AtlasManifest
+{
+    // relative to the manifest
+    pub image: PathBuf,
+    pub layout: TextureAtlasLayout,
+    // sorted so the file diffs cleanly
+    pub names: BTreeMap<String, usize>,
+}
+
+#[derive(Asset, TypePath, Debug)]
+pub struct Atlas
+{
+    pub image: Handle<Image>,
+    pub layout: TextureAtlasLayout,
+    pub names: HashMap<String, usize>,
+}
+
+impl Atlas
+{
+    pub fn index(&self, name: &str) -> Option<usize>
+    {
+        self.names.get(name).copied()
+    }
+
+    // normalized, for sampling the image directly
+    pub fn uv_rect(&self, name: &str) -> Option<Rect>
+    {
+        let rect = self.layout.textures.get(self.index(name)?)?.as_rect();
+        let size = self.layout.size.as_vec2();
+        Some(Rect::from_corners(rect.min / size, rect.max / size))
+    }
+
+    pub fn texel_size(&self) -> Vec2
+    {
+        1.0 / self.layout.size.as_vec2()
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum AtlasLoaderError
+{
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+    #[error("Name {0} points past the {1} textures in the layout")]
+    BadIndex(String, usize),
+}
+
+#[derive(Default)]
+pub struct AtlasLoader;
+
+impl AssetLoader for AtlasLoader
+{
+    type Asset = Atlas;
+    type Settings = ();
+    type Error = AtlasLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error>
+    {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let manifest = from_bytes::<AtlasManifest>(&bytes)?;
+
+        let count = manifest.layout.textures.len();
+        if let Some((name, _)) = manifest.names.iter().find(|(_, index)| **index >= count)
+        {
+            return Err(AtlasLoaderError::BadIndex(name.clone(), count));
+        }
+
+        let image_path = load_context.path().parent().map(|parent| parent.join(&manifest.image)).unwrap_or(manifest.image);
+        Ok(Atlas
+        {
+            image: load_context.load(image_path),
+            layout: manifest.layout,
+            names: manifest.names.into_iter().collect(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str]
+    {
+        &[EXTENSION]
+    }
+}
+
+pub struct AtlasPlugin;
+
+impl Plugin for AtlasPlugin
+{
+    fn build(&self, app: &mut App)
+    {
+        app.init_asset::<Atlas>()
+            .init_asset_loader::<AtlasLoader>();
+    }
+}
@@ -1,6 +1,9 @@
 
+pub mod atlas;
 pub mod ron;
 pub mod helper;
+pub mod tuning;
+pub mod watch;
 
 pub fn hello_bevyx()
 {
@@ -0,0 +1,122 @@
+use crate::ron::RonAssetPlugin;
+use crate::watch::AssetWatcher;
+
+use base::tuning::Tuning;
+
+use bevy::asset::AssetLoadFailedEvent;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::path::Path;
+
+// load failures by tuning type, the debug panel lists them
+#[derive(Resource, Debug, Default)]
+pub struct TuningErrors
+{
+    pub errors: BTreeMap<&'static str, String>,
+}
+
+#[derive(Resource)]
+struct TuningHandle<T: Asset>(Handle<T>);
+
+// the file only gets the defaults written when it doesn't exist yet, hand edits are kept
+fn read<T>(errors: &mut TuningErrors) -> T
+    where T: Tuning + TypePath + Default + serde::Serialize + for<'de> serde::Deserialize<'de>
+{
+    if !Path::new(base::assets::ASSETS_FOLDER).join(T::path()).exists()
+    {
+        let tuning = T::default();
+        tuning.save();
+        return tuning;
+    }
+
+    match T::try_load()
+    {
+        Ok(tuning) => tuning,
+        Err(err) =>
+        {
+            errors.errors.insert(T::short_type_path(), format!("{}: {}", T::path().display(), err));
+            T::default()
+        }
+    }
+}
+
+fn load<T>
+(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+)
+    where T: Tuning + Asset
+{
+    commands.insert_resource(TuningHandle::<T>(asset_server.load(T::path())));
+}
+
+fn watch<T>
+(
+    time: Res<Time>,
+    mut watcher: ResMut<AssetWatcher<T>>,
+    asset_server: Res<AssetServer>,
+)
+    where T: Tuning + 'static
+{
+    watcher.poll(&time, &asset_server, || [T::path().to_path_buf()]);
+}
+
+fn apply<T>
+(
+    mut events: EventReader<AssetEvent<T>>,
+    mut failures: EventReader<AssetLoadFailedEvent<T>>,
+    handle: Res<TuningHandle<T>>,
+    assets: Res<Assets<T>>,
+    mut tuning: ResMut<T>,
+    mut errors: ResMut<TuningErrors>,
+)
+    where T: Tuning + Asset + Resource + Clone
+{
+    for event in events.read()
+    {
+        if (event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = assets.get(&handle.0)
+        {
+            *tuning = loaded.clone();
+            errors.errors.remove(T::short_type_path());
+        }
+    }
+    for failure in failures.read()
+    {
+        if failure.id == handle.0.id()
+        {
+            errors.errors.insert(T::short_type_path(), failure.error.to_string());
+        }
+    }
+}
+
+// keeps the resource in step with its RON file, a broken file leaves the last good values in place
+pub struct TuningPlugin<T>(PhantomData<T>);
+
+impl<T> Default for TuningPlugin<T>
+{
+    fn default() -> Self
+    {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Plugin for TuningPlugin<T>
+    where T: Tuning + Asset + Resource + Clone + Default + serde::Serialize + for<'de> serde::Deserialize<'de>
+{
+    fn build(&self, app: &mut App)
+    {
+        app.init_resource::<TuningErrors>();
+        // read now so the resource is there before any system needs it, the asset only replaces it later
+        let tuning = read::<T>(&mut app.world_mut().resource_mut::<TuningErrors>());
+        app
+            .add_plugins(RonAssetPlugin::<T>::default())
+            .insert_resource(tuning)
+            .init_resource::<AssetWatcher<T>>()
+            .add_systems(Startup, load::<T>)
+            .add_systems(Update, (watch::<T>, apply::<T>.run_if(resource_exists::<TuningHandle<T>>)).chain());
+    }
+}
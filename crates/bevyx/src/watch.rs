@@ -0,0 +1,70 @@
+use base::assets::ASSETS_FOLDER;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+// modification times of asset files, for polling them without a file watcher
+#[derive(Debug, Default)]
+struct FileTimes
+{
+    modified: HashMap<PathBuf, SystemTime>,
+}
+
+impl FileTimes
+{
+    // true when the file changed since the last call, the first call only records it
+    fn changed(&mut self, path: &Path) -> bool
+    {
+        let Ok(modified) = std::fs::metadata(Path::new(ASSETS_FOLDER).join(path)).and_then(|metadata| metadata.modified()) else
+        {
+            return false;
+        };
+        self.modified.insert(path.to_path_buf(), modified).is_some_and(|previous| previous != modified)
+    }
+}
+
+// reloads asset files that changed on disk so they can be edited while playing, T only tells watchers apart
+#[derive(Resource)]
+pub struct AssetWatcher<T>
+{
+    timer: Timer,
+    times: FileTimes,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for AssetWatcher<T>
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+            times: default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> AssetWatcher<T>
+{
+    // files is only asked for when the timer is up
+    pub fn poll<I>(&mut self, time: &Time, asset_server: &AssetServer, files: impl FnOnce() -> I)
+        where I: IntoIterator<Item = PathBuf>
+    {
+        if !self.timer.tick(time.delta()).just_finished()
+        {
+            return;
+        }
+
+        for path in files()
+        {
+            if self.times.changed(&path)
+            {
+                asset_server.reload(path);
+            }
+        }
+    }
+}
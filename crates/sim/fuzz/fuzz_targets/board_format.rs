@@ -0,0 +1,47 @@
+#![no_main]
+
+// cargo fuzz run board_format, from crates/sim
+// imported boards must never panic, and whatever parses must hold to what the logic assumes
+
+use libfuzzer_sys::fuzz_target;
+use sim::board_format;
+use sim::grid::CellState;
+use sim::grid::Grid;
+
+fn check(grid: &Grid)
+{
+    for pos in grid.size().index2_space()
+    {
+        let state = grid.states[pos];
+        assert!(!state.contains(CellState::NonPlayable) || state == CellState::NonPlayable);
+        assert!(!state.contains(CellState::Flag | CellState::Revealed));
+
+        let mines = grid.neighbours(pos).filter(|neighbour| grid.states[*neighbour].contains(CellState::Mine)).count();
+        assert_eq!(grid.adjacency[pos] as usize, mines);
+    }
+}
+
+fuzz_target!(|data: &[u8]|
+{
+    if let Ok(grid) = board_format::from_mbf(data)
+    {
+        check(&grid);
+        let read = board_format::from_mbf(&board_format::to_mbf(&grid).unwrap()).unwrap();
+        assert_eq!(read.states, grid.states);
+    }
+
+    let Ok(text) = std::str::from_utf8(data) else
+    {
+        return;
+    };
+    if let Ok(grid) = board_format::from_ascii(text)
+    {
+        check(&grid);
+        let read = board_format::from_ascii(&board_format::to_ascii(&grid)).unwrap();
+        assert_eq!(read.states, grid.states);
+    }
+    if let Ok(grid) = board_format::from_ron(text)
+    {
+        check(&grid);
+    }
+});
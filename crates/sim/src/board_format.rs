@@ -0,0 +1,337 @@
+use crate::grid::Grid;
+use crate::grid::CellState;
+
+use base::extents::Extents;
+use base::point::Point;
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+// Readers and writers for hand made and community boards.
+//
+// ascii: one line per row, top row first, `*` mine, `.` safe, `#` non-playable
+// mbf: width u8, height u8, mine count u16 big endian, then an (x, y) u8 pair per mine, y = 0 is the top row
+// ron: the Grid itself, including revealed and flagged cells
+
+#[derive(Debug)]
+pub enum Error
+{
+    Empty,
+    InvalidCharacter{ line: usize, column: usize, character: char },
+    RaggedRow{ line: usize, expected: usize, found: usize },
+    Truncated{ expected: usize, found: usize },
+    MineOutOfBounds(Point),
+    DuplicateMine(Point),
+    InvalidState(Point),
+    TooLarge(Extents),
+    SizeMismatch,
+    UnknownExtension(std::path::PathBuf),
+    Ron(String),
+    Io(std::io::Error),
+}
+
+impl Display for Error
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Error::Empty => write!(f, "Board -- no cells"),
+            Error::InvalidCharacter{ line, column, character } => write!(f, "Board -- invalid character '{character}' at {line}:{column}"),
+            Error::RaggedRow{ line, expected, found } => write!(f, "Board -- line {line} has {found} cells, expected {expected}"),
+            Error::Truncated{ expected, found } => write!(f, "Board -- expected {expected} bytes, found {found}"),
+            Error::MineOutOfBounds(pos) => write!(f, "Board -- mine ({}, {}) out of bounds", pos.x, pos.y),
+            Error::DuplicateMine(pos) => write!(f, "Board -- duplicate mine ({}, {})", pos.x, pos.y),
+            Error::InvalidState(pos) => write!(f, "Board -- cell ({}, {}) has states that can't go together", pos.x, pos.y),
+            Error::TooLarge(size) => write!(f, "Board -- {}x{} does not fit the format", size.width, size.height),
+            Error::SizeMismatch => write!(f, "Board -- cell data does not match the board size"),
+            Error::UnknownExtension(path) => write!(f, "Board -- unknown board format [{}]", path.display()),
+            Error::Ron(err) => write!(f, "Board -- {err}"),
+            Error::Io(err) => write!(f, "Board -- {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error
+{
+    fn from(err: std::io::Error) -> Self
+    {
+        Error::Io(err)
+    }
+}
+
+// rows are stored bottom up, text and mbf are written top down
+fn flip_y(size: Extents, y: i32) -> i32
+{
+    size.height - 1 - y
+}
+
+fn from_mines(size: Extents, mines: impl Iterator<Item = Point>) -> Result<Grid, Error>
+{
+    let mut grid = Grid::from_size(size);
+    grid.first_guess = false;
+    for pos in mines
+    {
+        let cell = grid.states.get_by_index2_mut(pos).ok_or(Error::MineOutOfBounds(pos))?;
+        if cell.contains(CellState::Mine)
+        {
+            return Err(Error::DuplicateMine(pos));
+        }
+        cell.insert(CellState::Mine);
+    }
+    grid.update_adjacency();
+    Ok(grid)
+}
+
+pub fn from_ascii(text: &str) -> Result<Grid, Error>
+{
+    let lines: Vec<&str> = text.lines().map(|line| line.trim_end()).collect();
+    // blank lines around the board are skipped, inside it they are a row with no cells
+    let (Some(first), Some(last)) = (lines.iter().position(|line| !line.is_empty()), lines.iter().rposition(|line| !line.is_empty())) else
+    {
+        return Err(Error::Empty);
+    };
+    let rows = &lines[first..=last];
+    let width = rows[0].chars().count();
+
+    let size = Extents{ width: width as i32, height: rows.len() as i32 };
+    let mut grid = Grid::from_size(size);
+    grid.first_guess = false;
+    for (index, row) in rows.iter().enumerate()
+    {
+        // numbered as in the file, from 1
+        let line = first + index;
+        let found = row.chars().count();
+        if found != width
+        {
+            return Err(Error::RaggedRow{ line: line + 1, expected: width, found });
+        }
+
+        let y = flip_y(size, index as i32);
+        for (column, character) in row.chars().enumerate()
+        {
+            let state = match character
+            {
+                '*' => CellState::Mine,
+                '.' => CellState::None,
+                '#' => CellState::NonPlayable,
+                _ => return Err(Error::InvalidCharacter{ line: line + 1, column: column + 1, character }),
+            };
+            grid.states[Point::new(column as i32, y)] = state;
+        }
+    }
+    grid.update_adjacency();
+    Ok(grid)
+}
+
+pub fn to_ascii(grid: &Grid) -> String
+{
+    let size = grid.size();
+    let mut text = String::with_capacity(size.num_elements() + size.height as usize);
+    for line in 0..size.height
+    {
+        let y = flip_y(size, line);
+        for x in 0..size.width
+        {
+            let state = grid.states[Point::new(x, y)];
+            text.push(if state.contains(CellState::NonPlayable)
+                { '#' }
+                else if state.contains(CellState::Mine)
+                { '*' }
+                else
+                { '.' });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+pub fn from_mbf(bytes: &[u8]) -> Result<Grid, Error>
+{
+    const HEADER: usize = 4;
+    if bytes.len() < HEADER
+    {
+        return Err(Error::Truncated{ expected: HEADER, found: bytes.len() });
+    }
+
+    let size = Extents{ width: bytes[0] as i32, height: bytes[1] as i32 };
+    if size.num_elements() == 0
+    {
+        return Err(Error::Empty);
+    }
+
+    let mine_count = u16::from_be_bytes([bytes[2], bytes[3]]) as usize;
+    let expected = HEADER + mine_count * 2;
+    if bytes.len() < expected
+    {
+        return Err(Error::Truncated{ expected, found: bytes.len() });
+    }
+
+    let mines = bytes[HEADER..expected]
+        .chunks_exact(2)
+        .map(|pair| Point::new(pair[0] as i32, flip_y(size, pair[1] as i32)));
+    from_mines(size, mines)
+}
+
+// non-playable cells have no mbf equivalent and are written as safe cells
+pub fn to_mbf(grid: &Grid) -> Result<Vec<u8>, Error>
+{
+    let size = grid.size();
+    let mines: Vec<Point> = grid.states
+        .enumerate2()
+        .filter(|(_, state)| state.contains(CellState::Mine))
+        .map(|(pos, _)| pos)
+        .collect();
+
+    let max = u8::MAX as i32;
+    if size.width > max || size.height > max || mines.len() > u16::MAX as usize
+    {
+        return Err(Error::TooLarge(size));
+    }
+
+    let mut bytes = Vec::with_capacity(4 + mines.len() * 2);
+    bytes.push(size.width as u8);
+    bytes.push(size.height as u8);
+    bytes.extend_from_slice(&(mines.len() as u16).to_be_bytes());
+    for pos in mines
+    {
+        bytes.push(pos.x as u8);
+        bytes.push(flip_y(size, pos.y) as u8);
+    }
+    Ok(bytes)
+}
+
+pub fn from_ron(text: &str) -> Result<Grid, Error>
+{
+    let mut grid: Grid = ron::de::from_str(text).map_err(|err| Error::Ron(err.to_string()))?;
+    let size = grid.states.size();
+    if size.width <= 0 || size.height <= 0
+    {
+        return Err(Error::Empty);
+    }
+    if size.width.checked_mul(size.height).is_none()
+    {
+        return Err(Error::TooLarge(size));
+    }
+    if grid.adjacency.size() != size
+        || grid.states.iter().count() != size.num_elements()
+        || grid.adjacency.iter().count() != size.num_elements()
+    {
+        return Err(Error::SizeMismatch);
+    }
+
+    // the logic assumes land is only land and a flag is never on an open cell
+    if let Some((pos, _)) = grid.states.enumerate2().find(|(_, state)|
+    {
+        (state.contains(CellState::NonPlayable) && **state != CellState::NonPlayable)
+            || state.contains(CellState::Flag | CellState::Revealed)
+    })
+    {
+        return Err(Error::InvalidState(pos));
+    }
+
+    // never trust adjacency from disk
+    grid.update_adjacency();
+    Ok(grid)
+}
+
+pub fn to_ron(grid: &Grid) -> Result<String, Error>
+{
+    ron::ser::to_string_pretty(grid, ron::ser::PrettyConfig::default()).map_err(|err| Error::Ron(err.to_string()))
+}
+
+// picks the format from the extension, .txt for ascii
+pub fn read_file(path: &std::path::Path) -> Result<Grid, Error>
+{
+    match path.extension().and_then(|ext| ext.to_str())
+    {
+        Some("txt") => from_ascii(&std::fs::read_to_string(path)?),
+        Some("mbf") => from_mbf(&std::fs::read(path)?),
+        Some("ron") => from_ron(&std::fs::read_to_string(path)?),
+        _ => Err(Error::UnknownExtension(path.to_path_buf())),
+    }
+}
+
+pub fn write_file(grid: &Grid, path: &std::path::Path) -> Result<(), Error>
+{
+    match path.extension().and_then(|ext| ext.to_str())
+    {
+        Some("txt") => std::fs::write(path, to_ascii(grid))?,
+        Some("mbf") => std::fs::write(path, to_mbf(grid)?)?,
+        Some("ron") => std::fs::write(path, to_ron(grid)?)?,
+        _ => return Err(Error::UnknownExtension(path.to_path_buf())),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const BOARD: &str = "\
+*..#
+.*.#
+....
+";
+
+    #[test]
+    fn test_ascii_round_trip()
+    {
+        let grid = from_ascii(BOARD).unwrap();
+        assert_eq!(grid.size(), Extents{ width: 4, height: 3 });
+        // first line is the top row
+        assert!(grid.states[Point::new(0, 2)].contains(CellState::Mine));
+        assert!(grid.states[Point::new(3, 1)].contains(CellState::NonPlayable));
+        assert_eq!(grid.adjacency[Point::new(1, 2)], 2);
+        assert_eq!(to_ascii(&grid), BOARD);
+    }
+
+    #[test]
+    fn test_ascii_errors()
+    {
+        assert!(matches!(from_ascii(""), Err(Error::Empty)));
+        assert!(matches!(from_ascii("..\n...\n"), Err(Error::RaggedRow{ line: 2, expected: 2, found: 3 })));
+        assert!(matches!(from_ascii("..\n.x\n"), Err(Error::InvalidCharacter{ line: 2, column: 2, character: 'x' })));
+        assert!(matches!(from_ascii("\n\n..\n\n..\n"), Err(Error::RaggedRow{ line: 4, expected: 2, found: 0 })));
+        assert!(matches!(from_ascii("\n..\n.x\n"), Err(Error::InvalidCharacter{ line: 3, column: 2, character: 'x' })));
+        assert_eq!(from_ascii("\n\n..\n*.\n\n").unwrap().size(), Extents{ width: 2, height: 2 });
+    }
+
+    #[test]
+    fn test_mbf_round_trip()
+    {
+        let grid = from_ascii(&BOARD.replace('#', ".")).unwrap();
+        let bytes = to_mbf(&grid).unwrap();
+        assert_eq!(bytes, vec![4, 3, 0, 2, 1, 1, 0, 0]);
+        let read = from_mbf(&bytes).unwrap();
+        assert_eq!(read.states, grid.states);
+        assert_eq!(read.adjacency, grid.adjacency);
+    }
+
+    #[test]
+    fn test_mbf_errors()
+    {
+        assert!(matches!(from_mbf(&[4, 3]), Err(Error::Truncated{ .. })));
+        assert!(matches!(from_mbf(&[4, 3, 0, 2, 1, 1]), Err(Error::Truncated{ expected: 8, found: 6 })));
+        assert!(matches!(from_mbf(&[4, 3, 0, 1, 4, 0]), Err(Error::MineOutOfBounds(_))));
+        assert!(matches!(from_mbf(&[4, 3, 0, 2, 1, 1, 1, 1]), Err(Error::DuplicateMine(_))));
+        assert!(matches!(from_mbf(&[0, 3, 0, 0]), Err(Error::Empty)));
+    }
+
+    #[test]
+    fn test_ron_round_trip()
+    {
+        let mut grid = from_ascii(BOARD).unwrap();
+        grid.states[Point::new(0, 0)].insert(CellState::Revealed);
+        let read = from_ron(&to_ron(&grid).unwrap()).unwrap();
+        assert_eq!(read.states, grid.states);
+        assert_eq!(read.adjacency, grid.adjacency);
+        assert!(matches!(from_ron("not a grid"), Err(Error::Ron(_))));
+
+        grid.states[Point::new(3, 1)].insert(CellState::Mine);
+        assert!(matches!(from_ron(&to_ron(&grid).unwrap()), Err(Error::InvalidState(_))));
+    }
+}
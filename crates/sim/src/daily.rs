@@ -0,0 +1,64 @@
+use crate::grid::Neighbourhood;
+use crate::grid_gen::BoardConfig;
+
+use base::random::RandomGenerator;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DailyRules
+{
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub density: f32,
+    pub water_level: f32,
+    pub neighbourhood: Neighbourhood,
+}
+
+// rotates one rule set per day, so a week covers every variant
+pub const DAILY_RULES: [DailyRules; 7] =
+[
+    DailyRules{ name: "Classic", width: 30, height: 16, density: 0.2, water_level: 1.0, neighbourhood: Neighbourhood::All },
+    DailyRules{ name: "Archipelago", width: 40, height: 40, density: 0.18, water_level: 0.1, neighbourhood: Neighbourhood::All },
+    DailyRules{ name: "Crosswise", width: 24, height: 24, density: 0.12, water_level: 1.0, neighbourhood: Neighbourhood::Flush },
+    DailyRules{ name: "Coastline", width: 60, height: 30, density: 0.2, water_level: -0.1, neighbourhood: Neighbourhood::All },
+    DailyRules{ name: "Bishop", width: 24, height: 24, density: 0.1, water_level: 1.0, neighbourhood: Neighbourhood::Diagonal },
+    DailyRules{ name: "Minefield", width: 30, height: 30, density: 0.25, water_level: 0.3, neighbourhood: Neighbourhood::All },
+    DailyRules{ name: "Open Sea", width: 80, height: 80, density: 0.16, water_level: 0.2, neighbourhood: Neighbourhood::All },
+];
+
+pub fn rules(day: u64) -> &'static DailyRules
+{
+    &DAILY_RULES[(day % DAILY_RULES.len() as u64) as usize]
+}
+
+// day is the number of days since the unix epoch, the board only depends on it
+pub fn board(day: u64) -> BoardConfig
+{
+    let rules = rules(day);
+    let seed = RandomGenerator::hash(day);
+    let mine_count = ((rules.width * rules.height) as f32 * rules.density) as u32;
+    BoardConfig
+    {
+        // RandomGenerator can't take a zero seed
+        seed: seed.max(1),
+        width: rules.width,
+        height: rules.height,
+        mine_count: mine_count.max(1),
+        water_level: rules.water_level,
+        neighbourhood: rules.neighbourhood,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_deterministic()
+    {
+        assert_eq!(board(20000), board(20000));
+        assert_ne!(board(20000).seed, board(20001).seed);
+        assert!(board(20000).same_rules(&board(20000 + DAILY_RULES.len() as u64)));
+    }
+}
@@ -0,0 +1,17 @@
+use base::point::Point;
+
+use thiserror::Error;
+
+// what the simulation can run into on boards that don't leave it enough room
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error
+{
+    #[error("Logic -- the preview at {}, {} no longer matches the grid", .0.x, .0.y)]
+    StalePreview(Point),
+    #[error("Mines -- a board needs at least one mine")]
+    NoMines,
+    #[error("Mines -- the board has no cells a mine can go on")]
+    NoPlayableCells,
+    #[error("Mines -- {needed} mines need moving but only {available} cells are free")]
+    NoRoomForMines{ needed: u32, available: u32 },
+}
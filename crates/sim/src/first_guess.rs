@@ -1,12 +1,15 @@
 use crate::grid::Grid;
 use crate::logic::LogicPreview;
 use crate::mines;
+use crate::Error;
 
+use base::point::Point;
 use base::random::RandomGenerator;
 
 pub trait FirstGuessLogic
 {
-    fn handle_guess(&self, grid: &mut Grid, rand: &mut RandomGenerator, preview: &LogicPreview);
+    // returns the cells it changed
+    fn handle_guess(&self, grid: &mut Grid, rand: &mut RandomGenerator, preview: &LogicPreview) -> Result<Vec<Point>, Error>;
 }
 
 #[derive(Debug, Default)]
@@ -17,9 +20,10 @@ pub struct SafeFirstGuessLogic
 impl FirstGuessLogic for SafeFirstGuessLogic
 {
     // TODO make async
-    fn handle_guess(&self, grid: &mut Grid, rand: &mut RandomGenerator, preview: &LogicPreview)
+    fn handle_guess(&self, grid: &mut Grid, rand: &mut RandomGenerator, preview: &LogicPreview) -> Result<Vec<Point>, Error>
     {
-        mines::move_mines(grid, rand, preview.pos);
+        let changed = mines::move_mines(grid, rand, preview.pos)?;
         grid.first_guess = false;
+        Ok(changed)
     }
 }
@@ -1,13 +1,16 @@
 
 use base::array2::Array2;
 use base::extents;
+use base::extents::Neighbours;
+use base::point::Point;
 
 use bitflags::bitflags;
 
 bitflags!
 {
     #[repr(transparent)]
-    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+    #[serde(transparent)]
     pub struct CellState: u8
     {
         const None = 0 << 0;
@@ -18,10 +21,36 @@ bitflags!
     }
 }
 
-#[derive(Debug, Clone)]
+// which cells count towards adjacency, chording and reveal cascades
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "reflect", derive(bevy_reflect::Reflect))]
+pub enum Neighbourhood
+{
+    #[default]
+    All,
+    Flush,
+    Diagonal,
+}
+
+impl Neighbourhood
+{
+    pub fn flags(&self) -> Neighbours
+    {
+        match self
+        {
+            Neighbourhood::All => Neighbours::All,
+            Neighbourhood::Flush => Neighbours::Flush,
+            Neighbourhood::Diagonal => Neighbours::Diagonal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Grid
 {
     pub first_guess: bool,
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
     pub states: Array2<CellState>,
     pub adjacency: Array2<u8>,
 }
@@ -33,6 +62,7 @@ impl Grid
         Self
         {
             first_guess: true,
+            neighbourhood: Neighbourhood::default(),
             states: Array2::new(width, height),
             adjacency: Array2::new(width, height),
         }
@@ -43,6 +73,7 @@ impl Grid
         Self
         {
             first_guess: true,
+            neighbourhood: Neighbourhood::default(),
             states: Array2::from_size(size),
             adjacency: Array2::from_size(size),
         }
@@ -53,6 +84,11 @@ impl Grid
         self.states.size()
     }
 
+    pub fn neighbours(&self, pos: Point) -> impl DoubleEndedIterator<Item = Point> + Clone + use<>
+    {
+        self.size().neighbours_of(pos, self.neighbourhood.flags())
+    }
+
     pub fn clear(&mut self)
     {
         self.states.fill_with(CellState::None);
@@ -64,18 +100,60 @@ impl Grid
         let size = self.states.size();
         for pos in size.index2_space()
         {
-            let mut adj = 0;
-            for neighbour_pos in size.neighbours::<{ base::extents::Neighbours::All.bits() }>(pos)
+            let adj = self.count_adjacent(pos);
+            self.adjacency.set_by_index2(pos, adj).unwrap();
+        }
+    }
+
+    // only pos and its neighbours can count a mine at pos, returns the cells that were recomputed
+    pub fn update_adjacency_around(&mut self, pos: Point) -> Vec<Point>
+    {
+        let cells: Vec<Point> = std::iter::once(pos).chain(self.neighbours(pos)).collect();
+        for &cell in &cells
+        {
+            self.adjacency[cell] = self.count_adjacent(cell);
+        }
+        cells
+    }
+
+    fn count_adjacent(&self, pos: Point) -> u8
+    {
+        let mut adj = 0;
+        for neighbour_pos in self.neighbours(pos)
+        {
+            let state = self.states.get_by_index2(neighbour_pos).unwrap();
+            if state.intersects(CellState::Mine)
             {
-                let state = self.states.get_by_index2(neighbour_pos).unwrap();
-                if state.intersects(CellState::Mine)
-                {
-                    adj += 1;
-                }
+                adj += 1;
             }
-            assert!(adj <= 8);
-            self.adjacency.set_by_index2(pos, adj).unwrap();
         }
+        assert!(adj <= 8);
+        adj
     }
 }
 
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::board_format;
+
+    #[test]
+    fn test_update_adjacency_around()
+    {
+        let mut grid = board_format::from_ascii("\
+*...
+....
+..*.
+....
+").unwrap();
+        grid.states[Point::new(1, 1)].insert(CellState::Mine);
+        let cells = grid.update_adjacency_around(Point::new(1, 1));
+        assert_eq!(cells.len(), 9);
+
+        let mut full = grid.clone();
+        full.update_adjacency();
+        assert_eq!(grid.adjacency, full.adjacency);
+    }
+}
@@ -1,12 +1,50 @@
 
 use crate::grid::Grid;
 use crate::grid::CellState;
+use crate::grid::Neighbourhood;
+use crate::mines;
+use crate::Error;
 
 use base::random::RandomGenerator;
 
 use noise::Perlin;
 use noise::NoiseFn;
 
+// everything needed to rebuild the same board, the seed drives both generation and the first guess
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BoardConfig
+{
+	pub seed: u64,
+	pub width: u32,
+	pub height: u32,
+	pub mine_count: u32,
+	pub water_level: f32,
+	#[serde(default)]
+	pub neighbourhood: Neighbourhood,
+}
+
+impl BoardConfig
+{
+	// same board layout, ignoring the seed
+	pub fn same_rules(&self, other: &BoardConfig) -> bool
+	{
+		self.width == other.width
+			&& self.height == other.height
+			&& self.mine_count == other.mine_count
+			&& self.water_level == other.water_level
+			&& self.neighbourhood == other.neighbourhood
+	}
+}
+
+pub fn generate(config: &BoardConfig, rand: &mut RandomGenerator) -> Result<Grid, Error>
+{
+	let mut grid = Grid::new(config.width as i32, config.height as i32);
+	grid.neighbourhood = config.neighbourhood;
+	initial_terrain(&mut grid, rand, config.water_level);
+	mines::initial_mines(&mut grid, rand, config.mine_count)?;
+	Ok(grid)
+}
+
 pub fn initial_terrain(grid: &mut Grid, _rand: &mut RandomGenerator, water_level: f32)
 {
 	assert!(water_level >= -1.0 && water_level <= 1.0);
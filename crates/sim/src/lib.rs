@@ -1,11 +1,21 @@
 
+pub mod board_format;
 pub mod grid;
 pub mod mines;
 pub mod logic;
+pub mod daily;
 pub mod grid_gen;
+pub mod puzzle;
+pub mod replay;
+pub mod solver;
 mod reveal;
 mod win_loss;
 mod first_guess;
+mod error;
+#[cfg(test)]
+mod properties;
+
+pub use error::Error;
 
 pub fn hello_sim()
 {
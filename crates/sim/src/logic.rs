@@ -5,10 +5,11 @@ use crate::reveal::RevealLogic;
 use crate::reveal::ClassicRevealLogic;
 use crate::win_loss::WinLossLogic;
 use crate::win_loss::ClassicWinLossLogic;
+use crate::win_loss::PuzzleWinLossLogic;
 use crate::first_guess::FirstGuessLogic;
 use crate::first_guess::SafeFirstGuessLogic;
+use crate::Error;
 
-use base::extents::Neighbours;
 use base::random::RandomGenerator;
 use base::point::Point;
 
@@ -50,7 +51,20 @@ pub struct LogicPreview
 pub struct GuessResult
 {
     pub pos: Point,
+    // in the order the reveal reached them, nearest first
     pub revealed: Vec<Point>,
+    // how many steps the reveal took to reach each revealed cell, the guess is 0
+    pub depths: Vec<u32>,
+    // the guess was on a mine
+    pub exploded: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FirstGuessResult
+{
+    pub guess: GuessResult,
+    // cells changed by moving mines out of the way, before the reveal
+    pub changed: Vec<Point>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +73,16 @@ pub struct FlagResult
     pub pos: Point,
 }
 
+// what an action did to the grid, so views can redo only the cells it touched
+#[derive(Debug, Clone)]
+pub enum ActionResult
+{
+    FirstGuess(FirstGuessResult),
+    Guess(GuessResult),
+    Flag(FlagResult),
+    Chord(GuessResult),
+}
+
 pub struct Logic
 {
     first_guess: Box<dyn FirstGuessLogic + Send + Sync>,
@@ -78,6 +102,17 @@ impl Logic
         }
     }
 
+    // same rules for revealing, but the game only ends on a reveal of a mine or a submit
+    pub fn puzzle() -> Self
+    {
+        Logic
+        {
+            first_guess: Box::new(SafeFirstGuessLogic{}),
+            reveal: Box::new(ClassicRevealLogic{}),
+            win_loss: Box::new(PuzzleWinLossLogic::default()),
+        }
+    }
+
     pub fn get_status(&self) -> WinStatus
     {
         self.win_loss.get_status()
@@ -118,88 +153,120 @@ impl Logic
         return LogicPreview{ pos, kind: PreviewKind::Flag, result: PreviewResult::Success };
     }
 
+    // the grid is unchanged when this fails
+    pub fn actualize_preview(&mut self, grid: &mut Grid, rand: &mut RandomGenerator, preview: &LogicPreview) -> Result<ActionResult, Error>
+    {
+        Ok(match preview.kind
+        {
+            PreviewKind::FirstGuess => ActionResult::FirstGuess(self.do_first_guess(grid, rand, preview)?),
+            PreviewKind::Guess => ActionResult::Guess(self.do_guess(grid, preview)?),
+            PreviewKind::Flag => ActionResult::Flag(self.do_flag(grid, preview)?),
+            PreviewKind::Chord => ActionResult::Chord(self.do_chord(grid, preview)?),
+        })
+    }
+
+    // a preview is only good for the grid it was made from
+    fn check_preview(current: LogicPreview, preview: &LogicPreview) -> Result<(), Error>
+    {
+        if current != *preview
+        {
+            return Err(Error::StalePreview(preview.pos));
+        }
+        Ok(())
+    }
+
+    pub fn submit(&mut self, grid: &Grid) -> WinStatus
+    {
+        self.win_loss.submit(grid);
+        self.win_loss.get_status()
+    }
+
     pub fn do_first_guess
     (
         &mut self,
         grid: &mut Grid,
         rand: &mut RandomGenerator,
         original_preview: &LogicPreview
-    ) -> GuessResult
+    ) -> Result<FirstGuessResult, Error>
     {
-        assert!(original_preview.kind == PreviewKind::FirstGuess);
-        assert!(self.preview_guess(grid, original_preview.pos) == *original_preview);
+        Self::check_preview(self.preview_guess(grid, original_preview.pos), original_preview)?;
 
         if original_preview.result == PreviewResult::Nothing
         {
-            return GuessResult{ pos: original_preview.pos, revealed: Vec::new() };
+            let guess = GuessResult{ pos: original_preview.pos, revealed: Vec::new(), depths: Vec::new(), exploded: false };
+            return Ok(FirstGuessResult{ guess, changed: Vec::new() });
         }
 
-        self.first_guess.handle_guess(grid, rand, original_preview);
+        let changed = self.first_guess.handle_guess(grid, rand, original_preview)?;
 
         // recompute preview after the first guess logic, there may no longer be a mine there
         let preview = self.preview_guess(grid, original_preview.pos);
         self.win_loss.handle_guess(grid, &preview);
         assert!(self.win_loss.get_status() != WinStatus::Loss);
-        let cells = self.reveal.reveal(grid, preview.pos);
+        let (revealed, depths) = self.reveal.reveal(grid, preview.pos).into_iter().unzip();
         self.win_loss.post_reveal(grid);
         assert!(self.win_loss.get_status() != WinStatus::Loss);
-        GuessResult
+        let guess = GuessResult
         {
             pos: preview.pos,
-            revealed: cells,
-        }
+            revealed,
+            depths,
+            exploded: false,
+        };
+        Ok(FirstGuessResult{ guess, changed })
     }
 
-    pub fn do_guess(&mut self, grid: &mut Grid, preview: &LogicPreview) -> GuessResult
+    pub fn do_guess(&mut self, grid: &mut Grid, preview: &LogicPreview) -> Result<GuessResult, Error>
     {
-        assert!(preview.kind == PreviewKind::Guess);
-        assert!(self.preview_guess(grid, preview.pos) == *preview);
+        Self::check_preview(self.preview_guess(grid, preview.pos), preview)?;
 
         if preview.result == PreviewResult::Nothing
         {
-            return GuessResult{ pos: preview.pos, revealed: Vec::new() };
+            return Ok(GuessResult{ pos: preview.pos, revealed: Vec::new(), depths: Vec::new(), exploded: false });
         }
 
         self.win_loss.handle_guess(grid, preview);
-        let cells = self.reveal.reveal(grid, preview.pos);
+        let (revealed, depths) = self.reveal.reveal(grid, preview.pos).into_iter().unzip();
         self.win_loss.post_reveal(grid);
-        GuessResult
+        Ok(GuessResult
         {
             pos: preview.pos,
-            revealed: cells,
-        }
+            revealed,
+            depths,
+            exploded: preview.result == PreviewResult::Fail,
+        })
     }
 
-    pub fn do_flag(&self, grid: &mut Grid, preview: &LogicPreview) -> FlagResult
+    pub fn do_flag(&self, grid: &mut Grid, preview: &LogicPreview) -> Result<FlagResult, Error>
     {
-        assert!(preview.kind == PreviewKind::Flag);
-        assert!(self.preview_flag(grid, preview.pos) == *preview);
+        Self::check_preview(self.preview_flag(grid, preview.pos), preview)?;
 
         if preview.result == PreviewResult::Nothing
         {
-            return FlagResult{ pos: preview.pos };
+            return Ok(FlagResult{ pos: preview.pos });
         }
 
         let cell = &mut grid.states.get_by_index2_mut(preview.pos).unwrap();
         cell.toggle(CellState::Flag);
-        FlagResult
+        Ok(FlagResult
         {
             pos: preview.pos,
-        }
+        })
     }
 
-    pub fn do_chord(&mut self, grid: &mut Grid, preview: &LogicPreview) -> GuessResult
+    pub fn do_chord(&mut self, grid: &mut Grid, preview: &LogicPreview) -> Result<GuessResult, Error>
     {
-        assert!(preview.kind == PreviewKind::Chord);
-        assert!(self.preview_guess(grid, preview.pos) == *preview);
+        Self::check_preview(self.preview_guess(grid, preview.pos), preview)?;
 
         if preview.result == PreviewResult::Nothing
         {
-            return GuessResult{ pos: preview.pos, revealed: Vec::new() };
+            return Ok(GuessResult{ pos: preview.pos, revealed: Vec::new(), depths: Vec::new(), exploded: false });
         }
 
+        // each neighbour starts its own reveal one step out from the chord
         let mut revealed = Vec::new();
-        for neighbour in grid.size().neighbours::<{ Neighbours::All.bits() }>(preview.pos)
+        let mut depths = Vec::new();
+        for neighbour in grid.neighbours(preview.pos)
         {
             let neighbour_preview = self.preview_guess(grid, neighbour);
             // TODO strict chording mode
@@ -209,15 +276,20 @@ impl Logic
             };
             
             self.win_loss.handle_guess(grid, &neighbour_preview);
-            let mut cells = self.reveal.reveal(grid, neighbour_preview.pos);
-            revealed.append(&mut cells);
+            for (cell, depth) in self.reveal.reveal(grid, neighbour_preview.pos)
+            {
+                revealed.push(cell);
+                depths.push(depth + 1);
+            }
             self.win_loss.post_reveal(grid);
         }
 
-        GuessResult
+        Ok(GuessResult
         {
             pos: preview.pos,
             revealed,
-        }
+            depths,
+            exploded: false,
+        })
     }
 }
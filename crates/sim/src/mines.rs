@@ -1,14 +1,19 @@
 
 use crate::grid::Grid;
 use crate::grid::CellState;
+use crate::Error;
 
 use base::point::Point;
 use base::random::RandomGenerator;
 use base::extents::Neighbours;
 
-pub fn initial_mines(grid: &mut Grid, rand: &mut RandomGenerator, count: u32)
+// places up to count mines, fewer if the board has less room
+pub fn initial_mines(grid: &mut Grid, rand: &mut RandomGenerator, count: u32) -> Result<(), Error>
 {
-	assert!(count > 0);
+	if count == 0
+	{
+		return Err(Error::NoMines);
+	}
 
 	let mut valid_locations = Vec::<u32>::new();
 	valid_locations.reserve(grid.size().num_elements());
@@ -21,10 +26,14 @@ pub fn initial_mines(grid: &mut Grid, rand: &mut RandomGenerator, count: u32)
 
 		valid_locations.push(i as u32);
 	}
-
-	rand.shuffle(&mut valid_locations[..]);
+	if valid_locations.is_empty()
+	{
+		return Err(Error::NoPlayableCells);
+	}
 
 	let max_mines = std::cmp::min(valid_locations.len() as u32, count);
+	rand.partial_shuffle(&mut valid_locations[..], max_mines as usize);
+
 	for i in 0..max_mines
 	{
 		let cell_index = valid_locations[i as usize];
@@ -34,19 +43,15 @@ pub fn initial_mines(grid: &mut Grid, rand: &mut RandomGenerator, count: u32)
 		*cell |= CellState::Mine;
 	}
 	grid.update_adjacency();
+	Ok(())
 }
 
-pub fn move_mines(grid: &mut Grid, rand: &mut RandomGenerator, safe_point: Point)
+// clears the safe point and its neighbours, the grid is left as it was if there is nowhere to put their mines
+// returns every cell whose state or adjacency changed
+pub fn move_mines(grid: &mut Grid, rand: &mut RandomGenerator, safe_point: Point) -> Result<Vec<Point>, Error>
 {
-	let mut unsafe_mines: u32 = 0;
-	for neighbour in grid.size().neighbours_self::<{ Neighbours::All.bits() }>(safe_point)
-	{
-		if grid.states[neighbour].contains(CellState::Mine)
-		{
-			unsafe_mines += 1;
-		}
-		grid.states[neighbour].remove(CellState::Mine);
-	}
+	let safe_cells = grid.size().neighbours_self::<{ Neighbours::All.bits() }>(safe_point);
+	let unsafe_mines = safe_cells.clone().filter(|neighbour| grid.states[*neighbour].contains(CellState::Mine)).count() as u32;
 
 	let mut valid_locations = Vec::<Point>::new();
 	valid_locations.reserve(grid.size().num_elements());
@@ -66,25 +71,76 @@ pub fn move_mines(grid: &mut Grid, rand: &mut RandomGenerator, safe_point: Point
 		valid_locations.push(i);
 	}
 
-	rand.shuffle(&mut valid_locations[..]);
+	if (valid_locations.len() as u32) < unsafe_mines
+	{
+		return Err(Error::NoRoomForMines{ needed: unsafe_mines, available: valid_locations.len() as u32 });
+	}
 
-	assert!(valid_locations.len() as u32 >= unsafe_mines);
-	let max_mines = std::cmp::min(valid_locations.len() as u32, unsafe_mines);
-	for i in 0..max_mines
+	let mut moved = Vec::<Point>::new();
+	for neighbour in safe_cells
+	{
+		if grid.states[neighbour].contains(CellState::Mine)
+		{
+			grid.states[neighbour].remove(CellState::Mine);
+			moved.push(neighbour);
+		}
+	}
+
+	rand.partial_shuffle(&mut valid_locations[..], unsafe_mines as usize);
+
+	for i in 0..unsafe_mines
 	{
 		let cell_index2 = valid_locations[i as usize];
 		let cell = &mut grid.states[cell_index2];
 		assert!(!cell.contains(CellState::NonPlayable));
 		assert!(!cell.contains(CellState::Mine));
 		*cell |= CellState::Mine;
+		moved.push(cell_index2);
 	}
 
-	grid.update_adjacency();
+	let mut changed = Vec::<Point>::new();
+	for pos in moved
+	{
+		changed.extend(grid.update_adjacency_around(pos));
+	}
+	changed.sort_by_key(|pos| (pos.y, pos.x));
+	changed.dedup();
 	
     for neighbour in grid.size().neighbours_self::<{ Neighbours::All.bits() }>(safe_point)
     {
     	assert!(!grid.states[neighbour].contains(CellState::Mine));
     }
+	Ok(changed)
 }
 
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::board_format;
+
+	#[test]
+	fn test_no_mines()
+	{
+		let mut grid = Grid::new(4, 4);
+		assert_eq!(initial_mines(&mut grid, &mut RandomGenerator::new(1), 0), Err(Error::NoMines));
+
+		grid.states.fill_with(CellState::NonPlayable);
+		assert_eq!(initial_mines(&mut grid, &mut RandomGenerator::new(1), 3), Err(Error::NoPlayableCells));
+	}
 
+	#[test]
+	fn test_no_room_leaves_grid()
+	{
+		// every free cell is next to the guess
+		let mut grid = board_format::from_ascii("\
+**.
+*..
+...
+").unwrap();
+		let before = grid.clone();
+		let result = move_mines(&mut grid, &mut RandomGenerator::new(1), Point::new(1, 1));
+		assert_eq!(result, Err(Error::NoRoomForMines{ needed: 3, available: 0 }));
+		assert_eq!(grid.states, before.states);
+	}
+}
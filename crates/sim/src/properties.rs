@@ -0,0 +1,168 @@
+// invariants checked over random boards and random games, every case is rebuilt from its number so a failure can be rerun alone
+
+use crate::grid::CellState;
+use crate::grid::Grid;
+use crate::grid::Neighbourhood;
+use crate::grid_gen;
+use crate::grid_gen::BoardConfig;
+use crate::logic::Logic;
+use crate::logic::WinStatus;
+use crate::replay::Action;
+use crate::replay::Replay;
+use crate::replay::ReplayResult;
+use crate::Error;
+
+use base::point::Point;
+use base::random::RandomGenerator;
+
+const CASES: u64 = 64;
+const MAX_SIZE: u32 = 16;
+
+fn random_config(rand: &mut RandomGenerator) -> BoardConfig
+{
+    let width = rand.range(1..MAX_SIZE + 1);
+    let height = rand.range(1..MAX_SIZE + 1);
+    BoardConfig
+    {
+        // zero isn't a valid seed
+        seed: rand.random::<u64>() | 1,
+        width,
+        height,
+        // mostly playable densities, crowded boards still come up on small sizes
+        mine_count: rand.range(1..(width * height).div_ceil(4) + 1),
+        water_level: rand.range(-1.0..1.0),
+        neighbourhood: [Neighbourhood::All, Neighbourhood::Flush, Neighbourhood::Diagonal][rand.range(0..3usize)],
+    }
+}
+
+fn check_grid(grid: &Grid, case: u64)
+{
+    for pos in grid.size().index2_space()
+    {
+        let state = grid.states[pos];
+        // ClassicWinLossLogic::validate relies on both of these
+        assert!(!state.contains(CellState::NonPlayable) || state == CellState::NonPlayable, "case {case}: {state:?} at {pos:?}");
+        assert!(!state.contains(CellState::Flag | CellState::Revealed), "case {case}: flag on a revealed cell at {pos:?}");
+
+        let mines = grid.neighbours(pos).filter(|neighbour| grid.states[*neighbour].contains(CellState::Mine)).count();
+        assert_eq!(grid.adjacency[pos] as usize, mines, "case {case}: adjacency at {pos:?}");
+    }
+}
+
+fn all_safe_revealed(grid: &Grid) -> bool
+{
+    grid.states.iter().all(|state| state.intersects(CellState::Mine | CellState::NonPlayable | CellState::Revealed))
+}
+
+// half the primary actions go to a cell known to be safe, otherwise games rarely get far enough to win
+fn random_action(grid: &Grid, rand: &mut RandomGenerator) -> Action
+{
+    let size = grid.size();
+    let random_pos = |rand: &mut RandomGenerator| Point::new(rand.range(0..size.width), rand.range(0..size.height));
+    match rand.range(0..4)
+    {
+        0 => Action::Secondary(random_pos(rand)),
+        1 =>
+        {
+            let safe: Vec<Point> = size.index2_space()
+                .filter(|pos| !grid.states[*pos].intersects(CellState::Mine | CellState::NonPlayable | CellState::Revealed))
+                .collect();
+            if safe.is_empty()
+            {
+                Action::Primary(random_pos(rand))
+            }
+            else
+            {
+                Action::Primary(safe[rand.range(0..safe.len())])
+            }
+        }
+        _ => Action::Primary(random_pos(rand)),
+    }
+}
+
+#[test]
+fn test_generated_boards()
+{
+    for case in 1..=CASES
+    {
+        let config = random_config(&mut RandomGenerator::new(case));
+        let mut rand = RandomGenerator::new(config.seed);
+        match grid_gen::generate(&config, &mut rand)
+        {
+            Ok(grid) =>
+            {
+                check_grid(&grid, case);
+                let playable = grid.states.iter().filter(|state| !state.contains(CellState::NonPlayable)).count();
+                let mines = grid.states.iter().filter(|state| state.contains(CellState::Mine)).count();
+                assert_eq!(mines, playable.min(config.mine_count as usize), "case {case}");
+            }
+            Err(err) => assert_eq!(err, Error::NoPlayableCells, "case {case}"),
+        }
+    }
+}
+
+#[test]
+fn test_random_games()
+{
+    for case in 1..=CASES
+    {
+        let mut choices = RandomGenerator::new(case);
+        let config = random_config(&mut choices);
+        // the same generator carries on into the game, like a replay does
+        let mut rand = RandomGenerator::new(config.seed);
+        let Ok(mut grid) = grid_gen::generate(&config, &mut rand) else
+        {
+            continue;
+        };
+
+        let mut logic = Logic::new();
+        let mut replay = Replay::new(config.clone());
+        let mut refused = false;
+        for _ in 0..grid.size().num_elements() * 2
+        {
+            let action = random_action(&grid, &mut choices);
+            let before = grid.clone();
+            let revealed = match replay.apply(&mut logic, &mut grid, &mut rand, action)
+            {
+                Ok(result) =>
+                {
+                    let revealed = result.is_some() && matches!(action, Action::Primary(_));
+                    if before.first_guess && revealed
+                    {
+                        assert_ne!(logic.get_status(), WinStatus::Loss, "case {case}: lost on the first guess");
+                    }
+                    revealed
+                }
+                Err(Error::NoRoomForMines{ .. }) =>
+                {
+                    assert!(before.first_guess, "case {case}");
+                    assert_eq!(grid.states, before.states, "case {case}: a refused first guess changed the grid");
+                    refused = true;
+                    break;
+                }
+                Err(err) => panic!("case {case}: {err}"),
+            };
+
+            check_grid(&grid, case);
+            let status = logic.get_status();
+            if status == WinStatus::Loss
+            {
+                break;
+            }
+            // only reveals decide a win, flags leave it as it was
+            if revealed
+            {
+                assert_eq!(status == WinStatus::Win, all_safe_revealed(&grid), "case {case}: win without every safe cell revealed or the other way round");
+            }
+            if status == WinStatus::Win
+            {
+                break;
+            }
+        }
+
+        if !refused
+        {
+            assert_eq!(replay.simulate(), ReplayResult{ status: logic.get_status(), moves: replay.moves() }, "case {case}: replay diverged");
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::board_format;
+use crate::grid::Grid;
+use crate::grid::CellState;
+
+use base::point::Point;
+
+const REVEALED: char = 'o';
+
+// a hand made level, rows use the ascii board format plus `o` for a safe cell that starts revealed
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Puzzle
+{
+    pub name: String,
+    pub rows: Vec<String>,
+}
+
+impl Puzzle
+{
+    pub fn grid(&self) -> Result<Grid, board_format::Error>
+    {
+        let text = self.rows.join("\n");
+        let mut grid = board_format::from_ascii(&text.replace(REVEALED, "."))?;
+
+        let height = grid.size().height;
+        for (line, row) in self.rows.iter().filter(|row| !row.trim_end().is_empty()).enumerate()
+        {
+            let y = height - 1 - line as i32;
+            for (x, character) in row.chars().enumerate()
+            {
+                if character == REVEALED
+                {
+                    grid.states[Point::new(x as i32, y)].insert(CellState::Revealed);
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PuzzlePack
+{
+    pub puzzles: Vec<Puzzle>,
+}
+
+impl PuzzlePack
+{
+    // levels are played in order, once every level is solved it starts over
+    pub fn next_level(&self, is_solved: impl Fn(&Puzzle) -> bool) -> Option<usize>
+    {
+        if self.puzzles.is_empty()
+        {
+            return None;
+        }
+        Some(self.puzzles.iter().position(|puzzle| !is_solved(puzzle)).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::logic::Logic;
+    use crate::logic::WinStatus;
+    use crate::solver;
+
+    fn puzzle(rows: &[&str]) -> Puzzle
+    {
+        Puzzle
+        {
+            name: "test".to_owned(),
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_grid()
+    {
+        let grid = puzzle(&["oo.", "o.*"]).grid().unwrap();
+        assert!(grid.states[Point::new(0, 1)].contains(CellState::Revealed));
+        assert!(grid.states[Point::new(0, 0)].contains(CellState::Revealed));
+        assert!(!grid.states[Point::new(1, 0)].contains(CellState::Revealed));
+        assert!(grid.states[Point::new(2, 0)].contains(CellState::Mine));
+        assert!(!grid.first_guess);
+    }
+
+    #[test]
+    fn test_submit()
+    {
+        let mut grid = puzzle(&["oo.", "o.*"]).grid().unwrap();
+        let mut logic = Logic::puzzle();
+        grid.states[Point::new(1, 1)].insert(CellState::Flag);
+        assert_eq!(logic.submit(&grid), WinStatus::Loss);
+
+        let mut logic = Logic::puzzle();
+        grid.states[Point::new(1, 1)].remove(CellState::Flag);
+        grid.states[Point::new(2, 0)].insert(CellState::Flag);
+        assert_eq!(logic.submit(&grid), WinStatus::Win);
+    }
+
+    #[test]
+    fn test_shipped_pack()
+    {
+        let pack: PuzzlePack = ron::de::from_str(include_str!("../../../assets/puzzles/pack.puzzles.ron")).unwrap();
+        assert!(!pack.puzzles.is_empty());
+        for puzzle in &pack.puzzles
+        {
+            let grid = puzzle.grid().unwrap();
+            assert!(solver::is_solvable(&grid, None), "{} needs a guess", puzzle.name);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::grid::Grid;
+use crate::grid_gen;
+use crate::grid_gen::BoardConfig;
+use crate::logic::ActionResult;
+use crate::logic::Logic;
+use crate::logic::LogicPreview;
+use crate::logic::PreviewResult;
+use crate::logic::WinStatus;
+use crate::Error;
+
+use base::point::Point;
+use base::random::RandomGenerator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Action
+{
+    Primary(Point),
+    Secondary(Point),
+}
+
+impl Action
+{
+    pub fn pos(&self) -> Point
+    {
+        match self
+        {
+            Action::Primary(pos) | Action::Secondary(pos) => *pos,
+        }
+    }
+
+    pub fn preview(&self, logic: &Logic, grid: &Grid) -> LogicPreview
+    {
+        match self
+        {
+            Action::Primary(pos) => logic.preview_guess(grid, *pos),
+            Action::Secondary(pos) => logic.preview_flag(grid, *pos),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayResult
+{
+    pub status: WinStatus,
+    pub moves: u32,
+}
+
+// a board and every action that changed it, enough to re-simulate a whole game
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Replay
+{
+    pub board: BoardConfig,
+    pub actions: Vec<Action>,
+}
+
+impl Replay
+{
+    pub fn new(board: BoardConfig) -> Self
+    {
+        Self
+        {
+            board,
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn moves(&self) -> u32
+    {
+        self.actions.len() as u32
+    }
+
+    // applies the action and records it if it did anything, returns None for no-ops
+    pub fn apply
+    (
+        &mut self,
+        logic: &mut Logic,
+        grid: &mut Grid,
+        rand: &mut RandomGenerator,
+        action: Action
+    ) -> Result<Option<ActionResult>, Error>
+    {
+        let preview = action.preview(logic, grid);
+        if preview.result == PreviewResult::Nothing
+        {
+            return Ok(None);
+        }
+
+        let result = logic.actualize_preview(grid, rand, &preview)?;
+        self.actions.push(action);
+        Ok(Some(result))
+    }
+
+    pub fn simulate(&self) -> ReplayResult
+    {
+        let mut rand = RandomGenerator::new(self.board.seed);
+        let mut logic = Logic::new();
+        let Ok(mut grid) = grid_gen::generate(&self.board, &mut rand) else
+        {
+            return ReplayResult{ status: logic.get_status(), moves: 0 };
+        };
+        let mut replay = Replay::new(self.board.clone());
+
+        for action in &self.actions
+        {
+            if logic.get_status() != WinStatus::InProgress
+            {
+                break;
+            }
+
+            // every recorded action must have done something, otherwise the log was tampered with
+            if !matches!(replay.apply(&mut logic, &mut grid, &mut rand, *action), Ok(Some(_)))
+            {
+                break;
+            }
+        }
+
+        ReplayResult
+        {
+            status: logic.get_status(),
+            moves: replay.moves(),
+        }
+    }
+
+    // the whole log must be consumed and reach a win in exactly the claimed number of moves
+    pub fn verify(&self, claimed_moves: u32) -> bool
+    {
+        let result = self.simulate();
+        result.status == WinStatus::Win
+            && result.moves == claimed_moves
+            && result.moves == self.moves()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::grid::CellState;
+
+    fn board() -> BoardConfig
+    {
+        BoardConfig
+        {
+            seed: 7,
+            width: 12,
+            height: 12,
+            mine_count: 20,
+            water_level: 1.0,
+            neighbourhood: Default::default(),
+        }
+    }
+
+    fn winning_replay() -> Replay
+    {
+        let board = board();
+        let mut rand = RandomGenerator::new(board.seed);
+        let mut grid = grid_gen::generate(&board, &mut rand).unwrap();
+        let mut logic = Logic::new();
+        let mut replay = Replay::new(board);
+
+        assert!(replay.apply(&mut logic, &mut grid, &mut rand, Action::Primary((6, 6).into())).unwrap().is_some());
+        for pos in grid.size().index2_space()
+        {
+            if grid.states[pos].intersects(CellState::Mine | CellState::Revealed | CellState::NonPlayable)
+            {
+                continue;
+            }
+            replay.apply(&mut logic, &mut grid, &mut rand, Action::Primary(pos)).unwrap();
+        }
+        assert_eq!(logic.get_status(), WinStatus::Win);
+        replay
+    }
+
+    #[test]
+    fn test_verify_win()
+    {
+        let replay = winning_replay();
+        assert!(replay.verify(replay.moves()));
+        assert!(!replay.verify(replay.moves() - 1));
+    }
+
+    #[test]
+    fn test_verify_tampered()
+    {
+        let mut replay = winning_replay();
+        replay.actions.pop();
+        assert!(!replay.verify(replay.moves()));
+
+        let mut replay = winning_replay();
+        replay.board.seed += 1;
+        assert!(!replay.verify(replay.moves()));
+    }
+}
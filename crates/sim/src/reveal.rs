@@ -1,12 +1,14 @@
 use crate::grid::Grid;
 use crate::grid::CellState;
 
-use base::extents::Neighbours;
 use base::point::Point;
 
+use std::collections::VecDeque;
+
 pub trait RevealLogic
 {
-    fn reveal(&self, grid: &mut Grid, pos: Point) -> Vec<Point>;
+    // revealed cells with how many steps the reveal took to reach them, pos is 0
+    fn reveal(&self, grid: &mut Grid, pos: Point) -> Vec<(Point, u32)>;
 }
 
 #[derive(Debug)]
@@ -21,8 +23,9 @@ impl ClassicRevealLogic
         &self,
         grid: &mut Grid,
         pos: Point,
-        revealed: &mut Vec<Point>,
-        pending: &mut Vec<Point>
+        depth: u32,
+        revealed: &mut Vec<(Point, u32)>,
+        pending: &mut VecDeque<(Point, u32)>
     )
     {
         let cell_state = grid.states.get_by_index2_mut(pos).unwrap();
@@ -32,7 +35,7 @@ impl ClassicRevealLogic
         }
 
         cell_state.insert(CellState::Revealed);
-        revealed.push(pos);
+        revealed.push((pos, depth));
 
         let cell_adj = grid.adjacency.get_by_index2(pos).unwrap();
         if *cell_adj != 0
@@ -40,24 +43,62 @@ impl ClassicRevealLogic
             return;
         }
 
-        for neighbour in grid.size().neighbours::<{ Neighbours::All.bits() }>(pos)
+        for neighbour in grid.neighbours(pos)
         {
-            pending.push(neighbour);
+            pending.push_back((neighbour, depth + 1));
         }
     }
 }
 
 impl RevealLogic for ClassicRevealLogic
 {
-    fn reveal(&self, grid: &mut Grid, pos: Point) -> Vec<Point>
+    fn reveal(&self, grid: &mut Grid, pos: Point) -> Vec<(Point, u32)>
     {
+        // breadth first, so cells come out in order of depth
         let mut revealed = Vec::new();
-        let mut pending = Vec::new();
-        pending.push(pos);
-        while let Some(point) = pending.pop()
+        let mut pending = VecDeque::new();
+        pending.push_back((pos, 0));
+        while let Some((point, depth)) = pending.pop_front()
         {
-            self.reveal_internal(grid, point, &mut revealed, &mut pending);
+            self.reveal_internal(grid, point, depth, &mut revealed, &mut pending);
         }
         revealed
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::board_format;
+
+    #[test]
+    fn test_breadth_first()
+    {
+        let mut grid = Grid::new(7, 5);
+        let origin = Point::new(2, 2);
+        let revealed = ClassicRevealLogic{}.reveal(&mut grid, origin);
+        assert_eq!(revealed.len(), 7 * 5);
+        assert_eq!(revealed[0], (origin, 0));
+
+        // on an open board every step reaches one ring further out
+        for (pos, depth) in &revealed
+        {
+            assert_eq!((**pos - *origin).abs().max_element() as u32, *depth);
+        }
+        assert!(revealed.iter().map(|(_, depth)| depth).is_sorted());
+    }
+
+    #[test]
+    fn test_depth_follows_the_reveal()
+    {
+        // the opening has to go round the wall, so the cell right of it is further than it looks
+        let mut grid = board_format::from_ascii("\
+.#.
+.#.
+...
+").unwrap();
+        let revealed = ClassicRevealLogic{}.reveal(&mut grid, Point::new(0, 2));
+        assert!(revealed.contains(&(Point::new(2, 2), 4)));
+    }
+}
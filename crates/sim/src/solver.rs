@@ -0,0 +1,236 @@
+use crate::grid::Grid;
+use crate::grid::CellState;
+use crate::logic::Logic;
+use crate::logic::PreviewKind;
+use crate::logic::PreviewResult;
+use crate::logic::WinStatus;
+
+use base::array2::Array2;
+use base::point::Point;
+use base::random::RandomGenerator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deduction
+{
+    Safe(Point),
+    Mine(Point),
+}
+
+// only reads what a player could see: revealed cells, their adjacency and the mines it has deduced
+#[derive(Debug, Clone)]
+pub struct Solver
+{
+    known_mines: Array2<bool>,
+}
+
+struct Constraint
+{
+    unknown: Vec<Point>,
+    remaining: i32,
+}
+
+fn deduce(deductions: &mut Vec<Deduction>, known_mines: &mut Array2<bool>, deduction: Deduction)
+{
+    if deductions.contains(&deduction)
+    {
+        return;
+    }
+    if let Deduction::Mine(pos) = deduction
+    {
+        known_mines[pos] = true;
+    }
+    deductions.push(deduction);
+}
+
+impl Solver
+{
+    pub fn new(grid: &Grid) -> Self
+    {
+        Self
+        {
+            known_mines: Array2::from_size(grid.size()),
+        }
+    }
+
+    pub fn is_known_mine(&self, pos: Point) -> bool
+    {
+        self.known_mines[pos]
+    }
+
+    fn constraint(&self, grid: &Grid, pos: Point) -> Option<Constraint>
+    {
+        let state = grid.states[pos];
+        if !state.contains(CellState::Revealed) || state.contains(CellState::Mine)
+        {
+            return None;
+        }
+
+        let mut unknown = Vec::new();
+        let mut remaining = grid.adjacency[pos] as i32;
+        for neighbour in grid.neighbours(pos)
+        {
+            if self.known_mines[neighbour]
+            {
+                remaining -= 1;
+                continue;
+            }
+
+            if !grid.states[neighbour].intersects(CellState::Revealed | CellState::NonPlayable)
+            {
+                unknown.push(neighbour);
+            }
+        }
+
+        if unknown.is_empty()
+        {
+            return None;
+        }
+        Some(Constraint{ unknown, remaining })
+    }
+
+    // one round of deductions from single cells and pairs of overlapping cells, the grid is not modified
+    pub fn step(&mut self, grid: &Grid) -> Vec<Deduction>
+    {
+        let constraints: Vec<Constraint> = grid.size()
+            .index2_space()
+            .filter_map(|pos| self.constraint(grid, pos))
+            .collect();
+
+        let mut deductions = Vec::new();
+
+        for constraint in &constraints
+        {
+            if constraint.remaining == 0
+            {
+                constraint.unknown.iter().for_each(|pos| deduce(&mut deductions, &mut self.known_mines, Deduction::Safe(*pos)));
+            }
+            else if constraint.remaining == constraint.unknown.len() as i32
+            {
+                constraint.unknown.iter().for_each(|pos| deduce(&mut deductions, &mut self.known_mines, Deduction::Mine(*pos)));
+            }
+        }
+
+        if !deductions.is_empty()
+        {
+            return deductions;
+        }
+
+        // subset rule, if a's unknowns are all shared with b then the difference holds b's extra mines
+        for a in &constraints
+        {
+            for b in &constraints
+            {
+                if a.unknown.len() >= b.unknown.len() || !a.unknown.iter().all(|pos| b.unknown.contains(pos))
+                {
+                    continue;
+                }
+
+                let extra: Vec<Point> = b.unknown.iter().filter(|pos| !a.unknown.contains(pos)).copied().collect();
+                let extra_mines = b.remaining - a.remaining;
+                if extra_mines == 0
+                {
+                    extra.iter().for_each(|pos| deduce(&mut deductions, &mut self.known_mines, Deduction::Safe(*pos)));
+                }
+                else if extra_mines == extra.len() as i32
+                {
+                    extra.iter().for_each(|pos| deduce(&mut deductions, &mut self.known_mines, Deduction::Mine(*pos)));
+                }
+            }
+        }
+
+        deductions
+    }
+}
+
+// a safe zero cell is where a player would want to start, None if the board has no openings
+pub fn find_start(grid: &Grid) -> Option<Point>
+{
+    grid.size().index2_space().find(|pos|
+    {
+        let state = grid.states[*pos];
+        !state.intersects(CellState::Mine | CellState::NonPlayable | CellState::Revealed) && grid.adjacency[*pos] == 0
+    })
+}
+
+// true if the board can be won from start without guessing, flags in the grid are ignored
+pub fn is_solvable(grid: &Grid, start: Option<Point>) -> bool
+{
+    let mut grid = grid.clone();
+    grid.first_guess = false;
+    for state in grid.states.iter_mut()
+    {
+        state.remove(CellState::Flag);
+    }
+
+    let mut logic = Logic::new();
+    // first_guess is off so the generator is never used
+    let mut rand = RandomGenerator::new(1);
+    if let Some(start) = start
+    {
+        let preview = logic.preview_guess(&grid, start);
+        if preview.kind != PreviewKind::Guess || preview.result != PreviewResult::Success
+        {
+            return false;
+        }
+        if logic.actualize_preview(&mut grid, &mut rand, &preview).is_err()
+        {
+            return false;
+        }
+    }
+
+    let mut solver = Solver::new(&grid);
+    while logic.get_status() == WinStatus::InProgress
+    {
+        let deductions = solver.step(&grid);
+        if deductions.is_empty()
+        {
+            break;
+        }
+
+        for deduction in deductions
+        {
+            if let Deduction::Safe(pos) = deduction
+            {
+                let preview = logic.preview_guess(&grid, pos);
+                if preview.kind == PreviewKind::Guess && logic.actualize_preview(&mut grid, &mut rand, &preview).is_err()
+                {
+                    return false;
+                }
+            }
+        }
+    }
+
+    logic.get_status() == WinStatus::Win
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::board_format;
+
+    #[test]
+    fn test_solvable()
+    {
+        let grid = board_format::from_ascii("\
+....
+....
+.*..
+....
+").unwrap();
+        assert!(is_solvable(&grid, find_start(&grid)));
+    }
+
+    #[test]
+    fn test_needs_guess()
+    {
+        // the classic 50/50, two cells that no revealed number can tell apart
+        let grid = board_format::from_ascii("\
+*.##
+.*##
+..##
+..##
+").unwrap();
+        assert!(!is_solvable(&grid, find_start(&grid)));
+    }
+}
@@ -4,7 +4,6 @@ use crate::logic::PreviewResult;
 use crate::logic::LogicPreview;
 use crate::logic::WinStatus;
 
-use base::extents::Neighbours;
 use base::point::Point;
 
 pub trait WinLossLogic
@@ -14,6 +13,56 @@ pub trait WinLossLogic
     fn get_status(&self) -> WinStatus;
     fn handle_guess(&mut self, grid: &Grid, preview: &LogicPreview);
     fn post_reveal(&mut self, grid: &Grid);
+    fn submit(&mut self, grid: &Grid);
+}
+
+fn check_guess(grid: &Grid, pos: Point) -> PreviewResult
+{
+    let Some(cell_state) = grid.states.get_by_index2(pos) else
+    {
+        return PreviewResult::Nothing;
+    };
+
+    if cell_state.intersects(CellState::Revealed | CellState::NonPlayable | CellState::Flag)
+    {
+        return PreviewResult::Nothing;
+    }
+
+    if cell_state.intersects(CellState::Mine)
+    {
+        PreviewResult::Fail
+    }
+    else
+    {
+        PreviewResult::Success
+    }
+}
+
+fn check_chord(grid: &Grid, pos: Point) -> PreviewResult
+{
+    let Some(cell_state) = grid.states.get_by_index2(pos) else
+    {
+        return PreviewResult::Nothing;
+    };
+
+    assert!(cell_state.contains(CellState::Revealed));
+    let mut success = true;
+    for neighbour in grid.neighbours(pos)
+    {
+        let neighbour = grid.states.get_by_index2(neighbour).unwrap();
+        let has_flag = neighbour.contains(CellState::Flag);
+        let has_mine = neighbour.contains(CellState::Mine);
+        success &= has_flag == has_mine;
+    }
+
+    if success
+    {
+        PreviewResult::Success
+    }
+    else
+    {
+        PreviewResult::Nothing
+    }
 }
 
 #[derive(Debug, Default)]
@@ -26,51 +75,12 @@ impl WinLossLogic for ClassicWinLossLogic
 {
     fn check_guess(&self, grid: &Grid, pos: Point) -> PreviewResult
     {
-        let Some(cell_state) = grid.states.get_by_index2(pos) else
-        {
-            return PreviewResult::Nothing;
-        };
-
-        if cell_state.intersects(CellState::Revealed | CellState::NonPlayable | CellState::Flag)
-        {
-            return PreviewResult::Nothing;
-        }
-
-        if cell_state.intersects(CellState::Mine)
-        {
-            PreviewResult::Fail
-        }
-        else
-        {
-            PreviewResult::Success
-        }
+        check_guess(grid, pos)
     }
 
     fn check_chord(&self, grid: &Grid, pos: Point) -> PreviewResult
     {
-        let Some(cell_state) = grid.states.get_by_index2(pos) else
-        {
-            return PreviewResult::Nothing;
-        };
-
-        assert!(cell_state.contains(CellState::Revealed));
-        let mut success = true;
-        for neighbour in grid.size().neighbours::<{ Neighbours::All.bits() }>(pos)
-        {
-            let neighbour = grid.states.get_by_index2(neighbour).unwrap();
-            let has_flag = neighbour.contains(CellState::Flag);
-            let has_mine = neighbour.contains(CellState::Mine);
-            success &= has_flag == has_mine;
-        }
-
-        if success
-        {
-            PreviewResult::Success
-        }
-        else
-        {
-            PreviewResult::Nothing
-        }
+        check_chord(grid, pos)
     }
 
     fn handle_guess(&mut self, _grid: &Grid, preview: &LogicPreview)
@@ -86,7 +96,7 @@ impl WinLossLogic for ClassicWinLossLogic
             return;
         }
     }
-    
+
     fn post_reveal(&mut self, grid: &Grid)
     {
         if self.status != WinStatus::InProgress
@@ -101,6 +111,11 @@ impl WinLossLogic for ClassicWinLossLogic
         }
     }
 
+    // classic games are decided by reveals alone
+    fn submit(&mut self, _grid: &Grid)
+    {
+    }
+
     fn get_status(&self) -> WinStatus
     {
         self.status
@@ -123,7 +138,7 @@ impl ClassicWinLossLogic
             {
                 continue;
             }
-            
+
             playable += 1;
             if cell.contains(CellState::Revealed)
             {
@@ -151,3 +166,66 @@ impl ClassicWinLossLogic
         }
     }
 }
+
+// puzzles are decided when the flags are submitted, every mine and nothing else must be flagged
+#[derive(Debug, Default)]
+pub struct PuzzleWinLossLogic
+{
+    status: WinStatus,
+}
+
+impl WinLossLogic for PuzzleWinLossLogic
+{
+    fn check_guess(&self, grid: &Grid, pos: Point) -> PreviewResult
+    {
+        check_guess(grid, pos)
+    }
+
+    fn check_chord(&self, grid: &Grid, pos: Point) -> PreviewResult
+    {
+        check_chord(grid, pos)
+    }
+
+    // revealing a mine is still a loss, a puzzle never needs a guess
+    fn handle_guess(&mut self, _grid: &Grid, preview: &LogicPreview)
+    {
+        if self.status == WinStatus::InProgress && preview.result == PreviewResult::Fail
+        {
+            self.status = WinStatus::Loss;
+        }
+    }
+
+    fn post_reveal(&mut self, _grid: &Grid)
+    {
+    }
+
+    fn submit(&mut self, grid: &Grid)
+    {
+        if self.status != WinStatus::InProgress
+        {
+            return;
+        }
+
+        self.status = if Self::flags_match(grid)
+        {
+            WinStatus::Win
+        }
+        else
+        {
+            WinStatus::Loss
+        };
+    }
+
+    fn get_status(&self) -> WinStatus
+    {
+        self.status
+    }
+}
+
+impl PuzzleWinLossLogic
+{
+    fn flags_match(grid: &Grid) -> bool
+    {
+        grid.states.iter().all(|cell| cell.contains(CellState::Flag) == cell.contains(CellState::Mine))
+    }
+}
@@ -0,0 +1,216 @@
+use base::tuning::Tuning;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::EnumIter, strum::Display, serde::Serialize, serde::Deserialize)]
+pub enum InputAction
+{
+    Reveal,
+    Flag,
+    Chord,
+    Pan,
+    Zoom,
+    ResetView,
+    Undo,
+    Hint,
+    Submit,
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    ToggleDebug,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InputBinding
+{
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    MouseWheel,
+}
+
+impl InputBinding
+{
+    // pointer bindings act where the mouse is, the rest act on the cell cursor
+    pub fn is_pointer(&self) -> bool
+    {
+        matches!(self, InputBinding::Mouse(_) | InputBinding::MouseWheel)
+    }
+}
+
+impl Display for InputBinding
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            InputBinding::Key(key) => write!(f, "{key:?}"),
+            InputBinding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            InputBinding::Gamepad(button) => write!(f, "Pad {button:?}"),
+            InputBinding::MouseWheel => write!(f, "Mouse Wheel"),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InputMap
+{
+    pub bindings: BTreeMap<InputAction, Vec<InputBinding>>,
+}
+
+impl Tuning for InputMap
+{
+    fn path() -> &'static std::path::Path
+    {
+        std::path::Path::new("tuning/input.ron")
+    }
+}
+
+impl Default for InputMap
+{
+    fn default() -> Self
+    {
+        use InputBinding::*;
+        let bindings = BTreeMap::from(
+        [
+            (InputAction::Reveal, vec![Mouse(MouseButton::Left), Key(KeyCode::Space), Gamepad(GamepadButton::South)]),
+            (InputAction::Flag, vec![Mouse(MouseButton::Right), Key(KeyCode::KeyF), Gamepad(GamepadButton::East)]),
+            (InputAction::Chord, vec![Key(KeyCode::KeyC), Gamepad(GamepadButton::West)]),
+            (InputAction::Pan, vec![Mouse(MouseButton::Middle)]),
+            (InputAction::Zoom, vec![MouseWheel]),
+            (InputAction::ResetView, vec![Key(KeyCode::KeyR), Gamepad(GamepadButton::Select)]),
+            (InputAction::Undo, vec![Key(KeyCode::KeyZ), Gamepad(GamepadButton::LeftTrigger)]),
+            (InputAction::Hint, vec![Key(KeyCode::KeyH), Gamepad(GamepadButton::North)]),
+            (InputAction::Submit, vec![Key(KeyCode::Enter), Gamepad(GamepadButton::Start)]),
+            (InputAction::CursorUp, vec![Key(KeyCode::KeyW), Key(KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)]),
+            (InputAction::CursorDown, vec![Key(KeyCode::KeyS), Key(KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)]),
+            (InputAction::CursorLeft, vec![Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft), Gamepad(GamepadButton::DPadLeft)]),
+            (InputAction::CursorRight, vec![Key(KeyCode::KeyD), Key(KeyCode::ArrowRight), Gamepad(GamepadButton::DPadRight)]),
+            (InputAction::ToggleDebug, vec![Key(KeyCode::Backquote)]),
+        ]);
+        Self{ bindings }
+    }
+}
+
+impl InputMap
+{
+    pub fn bindings(&self, action: InputAction) -> &[InputBinding]
+    {
+        self.bindings.get(&action).map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    pub fn is_bound(&self, action: InputAction, binding: InputBinding) -> bool
+    {
+        self.bindings(action).contains(&binding)
+    }
+
+    pub fn bind(&mut self, action: InputAction, binding: InputBinding)
+    {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding)
+        {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: InputAction, binding: InputBinding)
+    {
+        if let Some(bindings) = self.bindings.get_mut(&action)
+        {
+            bindings.retain(|bound| *bound != binding);
+        }
+    }
+}
+
+// reads actions through the input map instead of raw devices
+#[derive(SystemParam)]
+pub struct Actions<'w, 's>
+{
+    pub map: Res<'w, InputMap>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl Actions<'_, '_>
+{
+    fn check(&self, action: InputAction, filter: impl Fn(&InputBinding) -> bool, pressed: Pressed) -> bool
+    {
+        self.map.bindings(action).iter().filter(|binding| filter(binding)).any(|binding| match *binding
+        {
+            InputBinding::Key(key) => pressed.check(&self.keys, key),
+            InputBinding::Mouse(button) => pressed.check(&self.mouse, button),
+            InputBinding::Gamepad(button) => self.gamepads.iter().any(|gamepad| match pressed
+            {
+                Pressed::Held => gamepad.pressed(button),
+                Pressed::Just => gamepad.just_pressed(button),
+                Pressed::Released => gamepad.just_released(button),
+            }),
+            // the wheel has no held state, it is read as events by whoever is bound to it
+            InputBinding::MouseWheel => false,
+        })
+    }
+
+    pub fn pressed(&self, action: InputAction) -> bool
+    {
+        self.check(action, |_| true, Pressed::Held)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool
+    {
+        self.check(action, |_| true, Pressed::Just)
+    }
+
+    pub fn pressed_pointer(&self, action: InputAction) -> bool
+    {
+        self.check(action, InputBinding::is_pointer, Pressed::Held)
+    }
+
+    pub fn just_pressed_pointer(&self, action: InputAction) -> bool
+    {
+        self.check(action, InputBinding::is_pointer, Pressed::Just)
+    }
+
+    pub fn just_pressed_buttons(&self, action: InputAction) -> bool
+    {
+        self.check(action, |binding| !binding.is_pointer(), Pressed::Just)
+    }
+
+    pub fn just_released_pointer(&self, action: InputAction) -> bool
+    {
+        self.check(action, InputBinding::is_pointer, Pressed::Released)
+    }
+}
+
+// run condition for systems that only do something on an action
+pub fn action_just_pressed(action: InputAction) -> impl FnMut(Actions) -> bool + Clone
+{
+    move |actions: Actions| actions.just_pressed(action)
+}
+
+#[derive(Clone, Copy)]
+enum Pressed
+{
+    Held,
+    Just,
+    Released,
+}
+
+impl Pressed
+{
+    fn check<T: Copy + Eq + std::hash::Hash + Send + Sync + 'static>(&self, input: &ButtonInput<T>, value: T) -> bool
+    {
+        match self
+        {
+            Pressed::Held => input.pressed(value),
+            Pressed::Just => input.just_pressed(value),
+            Pressed::Released => input.just_released(value),
+        }
+    }
+}
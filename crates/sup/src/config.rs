@@ -0,0 +1,80 @@
+use base::tuning::Tuning;
+use sim::grid::Neighbourhood;
+use sim::grid_gen::BoardConfig;
+
+use bevy::prelude::*;
+
+// the board a new game starts with, the custom screen changes it for the session
+#[derive(Resource, Debug, Clone, serde::Deserialize, serde::Serialize, Asset, Reflect)]
+pub struct GameConfig
+{
+    pub guessing: bool,
+    pub width: u32,
+    pub height: u32,
+    pub mine_count: u32,
+    pub seed: u64,
+    pub water_level: f32,
+    pub neighbourhood: Neighbourhood,
+    // plays a board file from the assets folder instead of generating one
+    pub board_file: Option<std::path::PathBuf>,
+}
+
+impl GameConfig
+{
+    pub fn board(&self) -> BoardConfig
+    {
+        BoardConfig
+        {
+            seed: self.seed,
+            width: self.width,
+            height: self.height,
+            mine_count: self.mine_count,
+            water_level: self.water_level,
+            neighbourhood: self.neighbourhood,
+        }
+    }
+
+    pub fn set_board(&mut self, board: &BoardConfig)
+    {
+        self.width = board.width;
+        self.height = board.height;
+        self.mine_count = board.mine_count;
+        self.seed = board.seed;
+        self.water_level = board.water_level;
+        self.neighbourhood = board.neighbourhood;
+    }
+
+    pub fn sanitize(&mut self)
+    {
+        self.width = self.width.clamp(1, 10000);
+        self.height = self.height.clamp(1, 10000);
+        // tiny boards still get a mine, the first guess handles having no room to clear around it
+        self.mine_count = self.mine_count.clamp(1, (self.width * self.height).saturating_sub(9).max(1));
+    }
+}
+
+impl Tuning for GameConfig
+{
+    fn path() -> &'static std::path::Path
+    {
+        std::path::Path::new("tuning/game.ron")
+    }
+}
+
+impl Default for GameConfig
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            guessing: false,
+            width: 20,
+            height: 20,
+            mine_count: 20 * 20 / 3,
+            seed: 1,
+            water_level: 0.1,
+            neighbourhood: Neighbourhood::All,
+            board_file: None,
+        }
+    }
+}
@@ -1,2 +1,4 @@
 
+pub mod actions;
+pub mod config;
 pub mod schedule;
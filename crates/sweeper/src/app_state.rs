@@ -1,6 +1,7 @@
 
 use bevy::prelude::*;
 
+pub mod editor;
 pub mod frontend;
 pub mod gameplay;
 pub mod splash;
@@ -11,4 +12,5 @@ pub enum AppState
     Splash,
     Frontend,
     Gameplay,
+    Editor,
 }
@@ -0,0 +1,363 @@
+use crate::app_state::AppState;
+use crate::input;
+use crate::input::GameplayCamera;
+use crate::interactor::Interactor;
+
+use base::array2::Array2;
+use sim::board_format;
+use sim::grid::CellState;
+use sim::grid::Grid;
+use sim::solver;
+use sup::actions::InputAction;
+use sup::actions::action_just_pressed;
+use sup::config::GameConfig;
+use vis::board_vis_tuning::BoardVisTuning;
+use vis::grid_entities::GridChanged;
+use vis::grid_entities::GridVis;
+use vis::grid_entities;
+use vis::grid_lines;
+use vis::terrain_grid::CellType;
+use vis::terrain_grid::TerrainGrid;
+use vis::terrain_vis;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContext;
+use bevy_egui::EguiContextPass;
+use bevy_egui::egui;
+use strum::IntoEnumIterator;
+
+const BOARDS_FOLDER: &str = "boards";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::Display)]
+pub enum Brush
+{
+    Mine,
+    Land,
+    Water,
+    Flag,
+    Erase,
+}
+
+impl Brush
+{
+    fn apply(&self, state: CellState) -> CellState
+    {
+        let playable = state - CellState::NonPlayable;
+        match self
+        {
+            Brush::Mine => playable | CellState::Mine,
+            Brush::Land => CellState::NonPlayable,
+            Brush::Water => playable,
+            Brush::Flag => playable | CellState::Flag,
+            Brush::Erase => CellState::None,
+        }
+    }
+}
+
+// the board being authored, separate from GridVis which shows it fully revealed
+#[derive(Resource, Debug, Clone)]
+pub struct EditorBoard
+{
+    pub grid: Grid,
+}
+
+// kept across visits so the last file is reopened
+#[derive(Resource, Debug, Clone)]
+pub struct EditorTools
+{
+    pub brush: Brush,
+    pub file: String,
+    pub message: String,
+}
+
+impl Default for EditorTools
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            brush: Brush::Mine,
+            file: "custom.ron".to_owned(),
+            message: String::new(),
+        }
+    }
+}
+
+impl EditorTools
+{
+    fn board_path(&self) -> std::path::PathBuf
+    {
+        std::path::Path::new(BOARDS_FOLDER).join(&self.file)
+    }
+
+    fn save(&mut self, grid: &Grid) -> bool
+    {
+        let full_path = std::path::Path::new(base::assets::ASSETS_FOLDER).join(self.board_path());
+        let result = full_path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(board_format::Error::from)
+            .and_then(|_| board_format::write_file(grid, &full_path));
+        self.message = match &result
+        {
+            Ok(_) => format!("saved {}", self.board_path().display()),
+            Err(err) => err.to_string(),
+        };
+        result.is_ok()
+    }
+}
+
+#[derive(Component)]
+pub struct EditorAppState;
+
+impl EditorAppState
+{
+    fn on_enter
+    (
+        mut commands: Commands,
+        config: Res<GameConfig>,
+        mut tools: ResMut<EditorTools>,
+    )
+    {
+        let full_path = std::path::Path::new(base::assets::ASSETS_FOLDER).join(tools.board_path());
+        let grid = match board_format::read_file(&full_path)
+        {
+            Ok(grid) => grid,
+            Err(err) =>
+            {
+                tools.message = format!("new board, {err}");
+                let mut grid = Grid::new(config.width as i32, config.height as i32);
+                grid.first_guess = false;
+                grid
+            }
+        };
+
+        commands.insert_resource(TerrainGrid{ grid: Self::terrain(&grid) });
+        commands.insert_resource(GridVis{ grid: Self::display(&grid) });
+        commands.insert_resource(EditorBoard{ grid });
+    }
+
+    fn on_exit(mut commands: Commands)
+    {
+        commands.remove_resource::<EditorBoard>();
+    }
+
+    fn terrain(grid: &Grid) -> Array2<CellType>
+    {
+        let mut terrain = Array2::<CellType>::from_size(grid.size());
+        for i in grid.size().index_space()
+        {
+            if grid.states[i].contains(CellState::NonPlayable)
+            {
+                terrain[i] = CellType::Land;
+            }
+        }
+        terrain
+    }
+
+    // everything playable is shown revealed so mines and numbers are visible, flags stay covered
+    fn display(grid: &Grid) -> Grid
+    {
+        let mut display = grid.clone();
+        for state in display.states.iter_mut()
+        {
+            *state = Self::display_state(*state);
+        }
+        display
+    }
+
+    fn display_state(state: CellState) -> CellState
+    {
+        if state.intersects(CellState::NonPlayable | CellState::Flag)
+        {
+            state
+        }
+        else
+        {
+            state | CellState::Revealed
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn paint
+    (
+        camera_query: Single<(&Camera, &GlobalTransform), (With<Camera2d>, With<GameplayCamera>)>,
+        window: Single<&Window, With<PrimaryWindow>>,
+        mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+        mouse_buttons: Res<ButtonInput<MouseButton>>,
+        board_vis_tuning: Res<BoardVisTuning>,
+        tools: Res<EditorTools>,
+        mut board: ResMut<EditorBoard>,
+        mut grid_vis: ResMut<GridVis>,
+        mut terrain: ResMut<TerrainGrid>,
+        mut changes: EventWriter<GridChanged>,
+    )
+    {
+        if !mouse_buttons.pressed(MouseButton::Left)
+        {
+            return;
+        }
+
+        if egui_ctx.single_mut().is_ok_and(|mut ctx| ctx.get_mut().is_pointer_over_area())
+        {
+            return;
+        }
+
+        let (camera, camera_transform) = camera_query.into_inner();
+        let Some(cursor_position) = window.cursor_position() else
+        {
+            return;
+        };
+
+        let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_position) else
+        {
+            return;
+        };
+
+        let Some(pos) = Interactor::world_to_cell(&board.grid, &board_vis_tuning, &world_pos) else
+        {
+            return;
+        };
+
+        let state = board.grid.states[pos];
+        let painted = tools.brush.apply(state);
+        if painted == state
+        {
+            return;
+        }
+
+        board.grid.states[pos] = painted;
+        let cells = board.grid.update_adjacency_around(pos);
+        for &cell in &cells
+        {
+            grid_vis.grid.states[cell] = Self::display_state(board.grid.states[cell]);
+            grid_vis.grid.adjacency[cell] = board.grid.adjacency[cell];
+        }
+        changes.write(GridChanged::Cells(cells));
+
+        // the terrain mesh is rebuilt on change, so only touch it when land was painted
+        if painted.contains(CellState::NonPlayable) != state.contains(CellState::NonPlayable)
+        {
+            terrain.grid[pos] = if painted.contains(CellState::NonPlayable) { CellType::Land } else { CellType::None };
+        }
+    }
+
+    fn ui
+    (
+        mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+        mut tools: ResMut<EditorTools>,
+        board: Res<EditorBoard>,
+        mut config: ResMut<GameConfig>,
+        mut next_state: ResMut<NextState<AppState>>,
+    )
+    {
+        let Ok(mut ctx) = egui_ctx.single_mut() else
+        {
+            return;
+        };
+
+        egui::SidePanel::left("editor_panel")
+            .resizable(false)
+            .show(ctx.get_mut(), |ui|
+            {
+                let size = board.grid.size();
+                ui.label(format!("Editor {}x{}", size.width, size.height));
+
+                ui.separator();
+                for brush in Brush::iter()
+                {
+                    ui.selectable_value(&mut tools.brush, brush, brush.to_string());
+                }
+
+                ui.separator();
+                if ui.button("Validate").clicked()
+                {
+                    tools.message = match solver::find_start(&board.grid)
+                    {
+                        Some(start) if solver::is_solvable(&board.grid, Some(start)) => "solvable without guessing".to_owned(),
+                        Some(_) => "needs guessing".to_owned(),
+                        None => "no opening to start from".to_owned(),
+                    };
+                }
+
+                ui.separator();
+                ui.label("file (.ron, .txt, .mbf)");
+                ui.text_edit_singleline(&mut tools.file);
+                if ui.button("Save").clicked()
+                {
+                    tools.save(&board.grid);
+                }
+
+                if ui.button("Test Play").clicked() && tools.save(&board.grid)
+                {
+                    config.board_file = Some(tools.board_path());
+                    next_state.set(AppState::Gameplay);
+                }
+
+                if ui.button("Return").clicked()
+                {
+                    next_state.set(AppState::Frontend);
+                }
+
+                ui.separator();
+                ui.label(&tools.message);
+            });
+    }
+}
+
+impl Plugin for EditorAppState
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .init_resource::<EditorTools>()
+
+            // vis plugins are added by the gameplay state, only the systems are shared here
+            .add_systems
+            (
+                OnEnter(AppState::Editor),
+                (
+                    EditorAppState::on_enter,
+                    grid_entities::init_handles,
+                    grid_entities::spawn_chunks,
+                    grid_lines::spawn_lines,
+                    terrain_vis::startup,
+                    input::fit_board,
+                )
+                .chain()
+            )
+            .add_systems
+            (
+                OnExit(AppState::Editor),
+                (
+                    EditorAppState::on_exit,
+                    grid_entities::despawn_chunks,
+                    grid_lines::despawn_lines,
+                    terrain_vis::shutdown,
+                )
+            )
+            .add_systems
+            (
+                Update,
+                (
+                    input::camera_pan,
+                    input::camera_zoom,
+                    input::fit_board.run_if(action_just_pressed(InputAction::ResetView)),
+                    input::camera_clamp,
+                    EditorAppState::paint,
+                    grid_entities::sync_chunks,
+                    (terrain_vis::shutdown, terrain_vis::startup)
+                        .chain()
+                        .run_if(resource_changed::<TerrainGrid>),
+                )
+                .chain()
+                .run_if(in_state(AppState::Editor))
+            )
+            .add_systems
+            (
+                EguiContextPass,
+                EditorAppState::ui.run_if(in_state(AppState::Editor))
+            )
+            ;
+    }
+}
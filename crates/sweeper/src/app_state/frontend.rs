@@ -1,7 +1,9 @@
 
 use crate::screens;
 use crate::app_state::AppState;
-use crate::app_state::gameplay::GameConfig;
+
+use bevyx::tuning::TuningPlugin;
+use sup::config::GameConfig;
 
 use bevy::prelude::*;
 
@@ -15,8 +17,10 @@ pub struct ScreenUpdate
 
 impl FrontendAppState
 {
-    fn spawn(commands: Commands, asset_server: Res<AssetServer>)
+    fn spawn(commands: Commands, asset_server: Res<AssetServer>, mut config: ResMut<GameConfig>)
     {
+        // boards from the editor are only played through test play
+        config.board_file = None;
         screens::home::spawn(commands, asset_server);
     }
 
@@ -34,7 +38,7 @@ impl Plugin for FrontendAppState
         app
             .add_systems(OnEnter(AppState::Frontend), FrontendAppState::spawn)
             .add_systems(Update, FrontendAppState::update)
-            .insert_resource(GameConfig::default())
+            .add_plugins(TuningPlugin::<GameConfig>::default())
             ;
     }
 }
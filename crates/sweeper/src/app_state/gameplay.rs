@@ -1,26 +1,47 @@
 
 use crate::app_state::AppState;
+use crate::daily::DailyChallenge;
 use crate::input;
 use crate::interactor::Interactor;
+use crate::leaderboard::Leaderboard;
+use crate::leaderboard::LeaderboardEntry;
+use crate::minimap;
+use crate::puzzle;
+use crate::puzzle::ActivePuzzle;
 use crate::screens;
+use crate::screens::hud::HudMessage;
 use crate::screens::hud::HudScreen;
+use crate::stats::DailyResult;
+use crate::stats::Stats;
+use crate::touch;
 
 use base::random::RandomGenerator;
 use base::array2::Array2;
-use base::extents::Extents;
+use base::save::SaveData;
 use sim::grid::*;
+use sim::grid_gen::BoardConfig;
+use sim::logic::Logic;
+use sim::replay::Action;
+use sup::actions::InputAction;
+use sup::actions::action_just_pressed;
+use sup::config::GameConfig;
+use vis::animations;
+use vis::animations::BoardEffect;
 use vis::grid_entities::GridVis;
 use vis::grid_entities;
 use vis::grid_lines;
+use vis::minimap::Minimap;
 use vis::terrain_grid::CellType;
 use vis::terrain_grid::TerrainGrid;
 use vis::terrain_vis;
 use sim::logic::WinStatus;
-use sim::mines;
 use sim::grid_gen;
+use sim::board_format;
 
 use lunex::UiLayoutRoot;
 use bevy::prelude::*;
+use bevy_egui::EguiContextPass;
+use bevy_egui::input::egui_wants_any_pointer_input;
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 enum InitializeSet
@@ -43,35 +64,22 @@ pub enum SubState
     End,
 }
 
-#[derive(Resource, Debug, Clone)]
-pub struct GameConfig
+// plays back a recorded game instead of taking input
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback
 {
-    pub guessing: bool,
-    pub width: u32,
-    pub height: u32,
-    pub mine_count: u32,
+    pub actions: std::collections::VecDeque<Action>,
+    pub timer: Timer,
 }
 
-impl GameConfig
+impl ReplayPlayback
 {
-    pub fn sanitize(&mut self)
-    {
-        self.width = self.width.clamp(1, 10000);
-        self.height = self.height.clamp(1, 10000);
-        self.mine_count = self.mine_count.clamp(1, self.width * self.height - 9);
-    }
-}
-
-impl Default for GameConfig
-{
-    fn default() -> Self
+    pub fn new(actions: &[Action]) -> Self
     {
         Self
         {
-            guessing: false,
-            width: 20,
-            height: 20,
-            mine_count: 20 * 20 / 3,
+            actions: actions.iter().copied().collect(),
+            timer: Timer::from_seconds(0.25, TimerMode::Repeating),
         }
     }
 }
@@ -81,17 +89,56 @@ pub struct GameplayAppState;
 
 impl GameplayAppState
 {
+    // a config no board can be built from, like one that is all land, plays the default board instead
+    fn generate(config: &mut GameConfig) -> (BoardConfig, RandomGenerator, Grid)
+    {
+        let board = config.board();
+        let mut rand = RandomGenerator::new(board.seed);
+        match grid_gen::generate(&board, &mut rand)
+        {
+            Ok(grid) => (board, rand, grid),
+            Err(err) =>
+            {
+                eprintln!("failed to generate board with error [{}], playing the default board instead", err);
+                config.set_board(&GameConfig::default().board());
+                let board = config.board();
+                let mut rand = RandomGenerator::new(board.seed);
+                let grid = grid_gen::generate(&board, &mut rand).expect("the default board has room for its mines");
+                (board, rand, grid)
+            }
+        }
+    }
+
     fn on_enter(
         mut commands: Commands,
-        config: Res<GameConfig>,
+        mut config: ResMut<GameConfig>,
+        puzzle: Option<Res<ActivePuzzle>>,
+        daily: Option<Res<DailyChallenge>>,
+        playback: Option<Res<ReplayPlayback>>,
         asset_server: Res<AssetServer>,
         mut next_state: ResMut<NextState<SubState>> )
     {
-        let mut grid = Grid::new(config.width as i32, config.height as i32);
-        let mut rand = RandomGenerator::new(1);
-
-        grid_gen::initial_terrain(&mut grid, &mut rand, 0.1);
-        mines::initial_mines(&mut grid, &mut rand, config.mine_count);
+        // dailies and replays set the board they need, a board file would play a different one
+        let board_file = config.board_file.clone().filter(|_| daily.is_none() && playback.is_none());
+        let loaded = match (&puzzle, &board_file)
+        {
+            (Some(puzzle), _) => Some(puzzle.grid.clone()),
+            (None, Some(path)) => match board_format::read_file(&std::path::Path::new(base::assets::ASSETS_FOLDER).join(path))
+            {
+                Ok(grid) => Some(grid),
+                Err(err) =>
+                {
+                    eprintln!("failed to load board [{}] with error [{}], generating one instead", path.display(), err);
+                    None
+                }
+            },
+            (None, None) => None,
+        };
+        let (board, rand, grid) = match loaded
+        {
+            Some(grid) => (config.board(), RandomGenerator::new(config.seed), grid),
+            None => Self::generate(&mut config),
+        };
 
         let mut terrain = TerrainGrid
         {
@@ -109,7 +156,14 @@ impl GameplayAppState
             };
         }
 
-        commands.insert_resource(Interactor::new());
+        let interactor = match puzzle
+        {
+            Some(_) => Interactor::with_logic(board, rand, Logic::puzzle),
+            None => Interactor::new(board, rand),
+        };
+        commands.insert_resource(interactor);
+        commands.insert_resource(input::CellCursor::default());
+        commands.insert_resource(grid_entities::CellPreview::default());
         commands.insert_resource(GridVis{ grid });
         commands.insert_resource(terrain);
         next_state.set(SubState::Playing);
@@ -117,27 +171,101 @@ impl GameplayAppState
         screens::hud::spawn(commands, asset_server);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn on_update
     (
         mut commands: Commands,
         asset_server: Res<AssetServer>,
-        interactor: Res<Interactor>,
+        mut interactor: ResMut<Interactor>,
+        mut leaderboard: ResMut<Leaderboard>,
+        config: Res<GameConfig>,
+        playback: Option<Res<ReplayPlayback>>,
+        daily: Option<Res<DailyChallenge>>,
+        puzzle: Option<Res<ActivePuzzle>>,
+        mut stats: ResMut<Stats>,
         mut next_state: ResMut<NextState<SubState>>,
+        mut effects: EventWriter<BoardEffect>,
         hud_screen: Option<Single<Entity, (With<HudScreen>, With<UiLayoutRoot>)>>,
     )
     {
+        if let Some(notice) = interactor.take_notice()
+        {
+            commands.insert_resource(HudMessage(notice));
+        }
+
+        if let Some(err) = interactor.take_error()
+        {
+            eprintln!("action failed with error [{}]", err);
+        }
+
         let status =  interactor.logic().get_status();
         if status != WinStatus::InProgress
         {
+            // replays only rebuild generated boards
+            if status == WinStatus::Win && playback.is_none() && config.board_file.is_none() && puzzle.is_none() && !interactor.assisted()
+            {
+                let replay = interactor.replay().clone();
+                let entry = LeaderboardEntry{ moves: replay.moves(), replay };
+                if leaderboard.submit(entry)
+                {
+                    leaderboard.save();
+                }
+            }
+            // undo and hints would let a lost daily be replayed into a win
+            if let (Some(daily), None) = (daily, &playback) && !interactor.assisted()
+            {
+                let result = DailyResult{ won: status == WinStatus::Win, moves: interactor.replay().moves() };
+                if stats.record_daily(daily.day, result)
+                {
+                    stats.save();
+                }
+            }
+            if let (Some(puzzle), WinStatus::Win) = (&puzzle, status) && stats.record_puzzle(&puzzle.name)
+            {
+                stats.save();
+            }
+            if status == WinStatus::Loss
+            {
+                effects.write(BoardEffect::RevealMines);
+            }
             if let Some(hud) = hud_screen
             {
                 commands.entity(*hud).despawn();
             }
             println!("game finished, status: {:?}", status);
+            commands.insert_resource(grid_entities::CellPreview::default());
             screens::eog::spawn(commands, asset_server);
             next_state.set(SubState::End);
         }
     }
+
+    fn play_replay
+    (
+        time: Res<Time>,
+        mut playback: ResMut<ReplayPlayback>,
+        mut interactor: ResMut<Interactor>,
+        mut grid_vis: ResMut<GridVis>,
+    )
+    {
+        if !playback.timer.tick(time.delta()).just_finished()
+        {
+            return;
+        }
+
+        if let Some(action) = playback.actions.pop_front()
+        {
+            interactor.apply(&mut grid_vis.grid, action);
+        }
+    }
+
+    fn on_exit(mut commands: Commands)
+    {
+        commands.remove_resource::<ReplayPlayback>();
+        commands.remove_resource::<DailyChallenge>();
+        commands.remove_resource::<ActivePuzzle>();
+        commands.remove_resource::<input::Hint>();
+        commands.remove_resource::<HudMessage>();
+    }
 }
 
 impl Plugin for GameplayAppState
@@ -165,9 +293,11 @@ impl Plugin for GameplayAppState
             (
                 OnEnter(AppState::Gameplay),
                 (
-                    grid_entities::init_known,
+                    grid_entities::spawn_chunks,
                     grid_lines::spawn_lines,
-                    terrain_vis::startup
+                    terrain_vis::startup,
+                    vis::minimap::startup,
+                    input::fit_board,
                 )
                 .in_set(InitializeSet::AfterBoard)
             )
@@ -176,9 +306,11 @@ impl Plugin for GameplayAppState
             (
                 OnExit(AppState::Gameplay),
                 (
-                    grid_entities::destroy_known,
+                    GameplayAppState::on_exit,
+                    grid_entities::despawn_chunks,
                     grid_lines::despawn_lines,
-                    terrain_vis::shutdown
+                    terrain_vis::shutdown,
+                    vis::minimap::shutdown,
                 )
             )
 
@@ -189,12 +321,25 @@ impl Plugin for GameplayAppState
                 (
                     GameplayAppState::on_update,
                     input::camera_pan,
-                    input::camera_zoom,
-                    input::reveal_cell,
-                    grid_entities::sync_grid_entities::<grid_entities::Mine>,
-                    grid_entities::sync_grid_entities::<grid_entities::Flag>,
-                    grid_entities::sync_grid_entities::<grid_entities::Cover>,
-                    grid_entities::sync_adjacency,
+                    input::camera_zoom.run_if(not(egui_wants_any_pointer_input)),
+                    input::fit_board.run_if(action_just_pressed(InputAction::ResetView)),
+                    input::camera_clamp.after(input::camera_pan).after(input::camera_zoom).after(touch::touch_camera).after(input::camera_follow_cursor),
+                    input::reveal_cell.run_if(not(resource_exists::<ReplayPlayback>)).run_if(not(egui_wants_any_pointer_input)),
+                    touch::touch_actions.run_if(not(resource_exists::<ReplayPlayback>)),
+                    touch::touch_camera,
+                    GameplayAppState::play_replay.run_if(resource_exists::<ReplayPlayback>),
+                    puzzle::submit.run_if(resource_exists::<ActivePuzzle>),
+                    (
+                        input::move_cursor,
+                        input::cursor_actions.run_if(not(resource_exists::<ReplayPlayback>)),
+                        input::camera_follow_cursor,
+                        input::draw_cursor,
+                    ).chain(),
+                    input::undo.run_if(not(resource_exists::<ReplayPlayback>)),
+                    input::hint.run_if(not(resource_exists::<ReplayPlayback>)),
+                    input::draw_hint.run_if(resource_exists::<input::Hint>),
+                    grid_entities::sync_pressed,
+                    screens::hud::update_message.run_if(resource_exists_and_changed::<HudMessage>),
                 )
                 .run_if(in_state(AppState::Gameplay))
                 .run_if(in_state(SubState::Playing))
@@ -206,12 +351,37 @@ impl Plugin for GameplayAppState
                 Update,
                 (
                     input::camera_pan,
-                    input::camera_zoom,
+                    input::camera_zoom.run_if(not(egui_wants_any_pointer_input)),
+                    touch::touch_camera,
+                    input::fit_board.run_if(action_just_pressed(InputAction::ResetView)),
+                    input::camera_clamp.after(input::camera_pan).after(input::camera_zoom).after(touch::touch_camera),
+                    grid_entities::sync_pressed,
                 )
                 .run_if(in_state(AppState::Gameplay))
                 .run_if(in_state(SubState::End))
             )
 
+            // after anything in update could have changed the grid
+            .add_systems
+            (
+                PostUpdate,
+                (
+                    Interactor::send_changes,
+                    grid_entities::sync_chunks,
+                    animations::apply_effects,
+                    animations::animate_cells,
+                    animations::draw_explosions,
+                    vis::minimap::sync,
+                )
+                .chain()
+                .run_if(in_state(AppState::Gameplay))
+            )
+            .add_systems
+            (
+                EguiContextPass,
+                minimap::ui.run_if(in_state(AppState::Gameplay)).run_if(resource_exists::<Minimap>)
+            )
+
             // sets
             .configure_sets
             (
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+// present while the current game is the daily challenge for this day
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct DailyChallenge
+{
+    pub day: u64,
+}
+
+// days since the unix epoch in utc, everyone on the same date gets the same board
+pub fn today() -> u64
+{
+    const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+    let since_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    since_epoch.as_secs() / SECONDS_PER_DAY
+}
+
+// year, month, day from days since the unix epoch (Howard Hinnant's civil_from_days)
+pub fn civil_date(day: u64) -> (i64, u32, u32)
+{
+    let z = day as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
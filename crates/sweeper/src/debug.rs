@@ -1,4 +1,16 @@
 
+#[cfg(feature = "cheats")]
+mod cheats;
+
+use crate::inspector::InspectorPlugin;
+
+use bevyx::tuning::TuningErrors;
+use sup::actions::Actions;
+use sup::actions::InputAction;
+use sup::config::GameConfig;
+use vis::board_vis_tuning::BoardVisTuning;
+use vis::board_vis_tuning::TerrainTuning;
+
 use bevy::prelude::*;
 use bevy::render::camera::RenderTarget;
 use bevy::window::PrimaryWindow;
@@ -37,9 +49,11 @@ fn setup(mut commands: Commands)
 fn primary_window_ui
 (
     keys: Res<ButtonInput<KeyCode>>,
+    actions: Actions,
     mut debug_state: ResMut<DebugState>,
     mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
     secondary_window: Option<Single<&mut Window, Without<PrimaryWindow>>>,
+    tuning_errors: Option<Res<TuningErrors>>,
     mut app_exit_events: ResMut<Events<bevy::app::AppExit>>
 )
 {
@@ -47,7 +61,7 @@ fn primary_window_ui
     {
         app_exit_events.send(AppExit::Success);
     }
-    if keys.just_pressed(KeyCode::Backquote)
+    if actions.just_pressed(InputAction::ToggleDebug)
     {
         debug_state.show_menu = !debug_state.show_menu;
     }
@@ -68,25 +82,17 @@ fn primary_window_ui
                 ui.label("Cim Debug");
                 if let (true, Some(mut window)) = (ui.button("Dev Window").clicked(), secondary_window)
                 {
-                    window.visible = window.visible;
+                    window.visible = !window.visible;
                 }
             });
+            // tuning files that failed to load, the game keeps running on the last good values
+            for (name, error) in tuning_errors.iter().flat_map(|errors| errors.errors.iter())
+            {
+                ui.colored_label(egui::Color32::LIGHT_RED, format!("{}: {}", name, error));
+            }
         });
 }
 
-fn dev_window_ui
-(
-    _keys: Res<ButtonInput<KeyCode>>,
-    _debug_state: ResMut<DebugState>,
-    egui_ctx: Single<&mut EguiContext, Without<PrimaryWindow>>,
-)
-{
-    let mut ctx = egui_ctx.into_inner();
-    egui::Window::new("Hello").show(ctx.get_mut(), |ui| {
-        ui.label("world");
-    });
-}
-
 pub struct DebugPlugin;
 
 impl Plugin for DebugPlugin
@@ -97,7 +103,12 @@ impl Plugin for DebugPlugin
             .insert_resource(DebugState::default())
             .add_systems(Startup, setup)
             .add_systems(Update, primary_window_ui)
-            .add_systems(Update, dev_window_ui);
+            .add_plugins(InspectorPlugin::<BoardVisTuning>::default())
+            .add_plugins(InspectorPlugin::<TerrainTuning>::default())
+            .add_plugins(InspectorPlugin::<GameConfig>::default());
+
+        #[cfg(feature = "cheats")]
+        app.add_plugins(cheats::CheatsPlugin);
     }
 }
 
@@ -0,0 +1,360 @@
+use super::DebugState;
+use crate::app_state::AppState;
+use crate::input::CellCursor;
+use crate::input::GameplayCamera;
+use crate::interactor::Interactor;
+
+use base::point::Point;
+use base::random::RandomGenerator;
+use sim::grid::CellState;
+use sim::grid::Grid;
+use sim::replay::Action;
+use sim::solver::Deduction;
+use sim::solver::Solver;
+use sup::config::GameConfig;
+use vis::board_vis_tuning::BoardVisTuning;
+use vis::grid_entities::GridChanged;
+use vis::grid_entities::GridVis;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContext;
+use bevy_egui::EguiContextPass;
+use bevy_egui::egui;
+use bevy_egui::input::egui_wants_any_pointer_input;
+
+#[derive(Resource, Default)]
+struct Cheats
+{
+    show_mines: bool,
+    // the last board cell under the pointer, kept while the pointer is over the panel
+    hovered: Option<Point>,
+    // kept between steps so it remembers the mines it deduced
+    solver: Option<Solver>,
+    last_step: Option<usize>,
+}
+
+impl Cheats
+{
+    fn forget_solver(&mut self)
+    {
+        self.solver = None;
+        self.last_step = None;
+    }
+}
+
+enum Cheat
+{
+    RevealAll,
+    ToggleMine(Point),
+    ForceWin,
+    ForceLoss,
+    Regenerate,
+    SolverStep,
+}
+
+fn is_safe(grid: &Grid, pos: Point) -> bool
+{
+    !grid.states[pos].intersects(CellState::Mine | CellState::NonPlayable)
+}
+
+// every cell that isn't a mine, without going through the logic so the game doesn't end
+fn reveal_all(grid: &mut Grid)
+{
+    for state in grid.states.iter_mut()
+    {
+        if !state.intersects(CellState::Mine | CellState::NonPlayable)
+        {
+            state.remove(CellState::Flag);
+            state.insert(CellState::Revealed);
+        }
+    }
+    // a first guess now would move mines under revealed cells
+    grid.first_guess = false;
+}
+
+fn toggle_mine(grid: &mut Grid, pos: Point) -> bool
+{
+    if grid.states[pos].intersects(CellState::NonPlayable | CellState::Revealed)
+    {
+        return false;
+    }
+    grid.states[pos].toggle(CellState::Mine);
+    grid.update_adjacency();
+    true
+}
+
+// leaves one safe cell and guesses it, so the logic decides the win itself
+fn force_win(grid: &mut Grid, interactor: &mut Interactor)
+{
+    let safe: Vec<Point> = grid.size().index2_space().filter(|pos| is_safe(grid, *pos)).collect();
+    let Some(last) = safe.iter().find(|pos| !grid.states[**pos].contains(CellState::Revealed)).or(safe.first()).copied() else
+    {
+        return;
+    };
+
+    reveal_all(grid);
+    // puzzles also want every mine flagged
+    for state in grid.states.iter_mut()
+    {
+        state.set(CellState::Flag, state.contains(CellState::Mine));
+    }
+    grid.states[last].remove(CellState::Revealed);
+    interactor.apply(grid, Action::Primary(last));
+    interactor.submit(grid);
+}
+
+fn force_loss(grid: &mut Grid, interactor: &mut Interactor)
+{
+    let Some(mine) = grid.size().index2_space().find(|pos| grid.states[*pos].contains(CellState::Mine)) else
+    {
+        return;
+    };
+
+    // the first guess is always safe, it would move the mine away
+    grid.first_guess = false;
+    grid.states[mine].remove(CellState::Flag);
+    interactor.apply(grid, Action::Primary(mine));
+}
+
+// applies one round of deductions, revealing the safe cells and flagging the mines
+fn solver_step(grid: &mut Grid, interactor: &mut Interactor, solver: &mut Solver) -> usize
+{
+    let deductions = solver.step(grid);
+    for deduction in &deductions
+    {
+        match *deduction
+        {
+            Deduction::Safe(pos) => interactor.apply(grid, Action::Primary(pos)),
+            Deduction::Mine(pos) if !grid.states[pos].contains(CellState::Flag) => interactor.apply(grid, Action::Secondary(pos)),
+            Deduction::Mine(_) => {}
+        }
+    }
+    deductions.len()
+}
+
+fn track_pointer
+(
+    camera_query: Single<(&Camera, &GlobalTransform), With<GameplayCamera>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    grid_vis: Res<GridVis>,
+    mut cheats: ResMut<Cheats>,
+)
+{
+    let (camera, camera_transform) = camera_query.into_inner();
+    let hovered = window
+        .cursor_position()
+        .and_then(|cursor_position| camera.viewport_to_world_2d(camera_transform, cursor_position).ok())
+        .and_then(|point| Interactor::world_to_cell(&grid_vis.grid, &board_vis_tuning, &point));
+    if hovered.is_some()
+    {
+        cheats.hovered = hovered;
+    }
+}
+
+fn draw_mines
+(
+    mut gizmos: Gizmos,
+    board_vis_tuning: Res<BoardVisTuning>,
+    grid_vis: Res<GridVis>,
+)
+{
+    let grid = &grid_vis.grid;
+    for pos in grid.size().index2_space().filter(|pos| grid.states[*pos].contains(CellState::Mine))
+    {
+        let center = (pos.as_vec2() + Vec2::splat(0.5)) * board_vis_tuning.cell_size;
+        gizmos.circle_2d(center, board_vis_tuning.cell_size.min_element() * 0.3, bevy::color::palettes::basic::RED);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn ui
+(
+    mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+    debug_state: Res<DebugState>,
+    cursor: Res<CellCursor>,
+    mut cheats: ResMut<Cheats>,
+    mut grid_vis: ResMut<GridVis>,
+    mut interactor: ResMut<Interactor>,
+    mut config: ResMut<GameConfig>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut changes: EventWriter<GridChanged>,
+)
+{
+    if !debug_state.show_menu
+    {
+        return;
+    }
+    let Ok(mut ctx) = egui_ctx.single_mut() else
+    {
+        return;
+    };
+
+    // the keyboard cursor wins over the pointer when it is showing
+    let target = cursor.pos.filter(|_| cursor.active).or(cheats.hovered);
+    let mut cheat = None;
+    egui::Window::new("Cheats").show(ctx.get_mut(), |ui|
+    {
+        ui.checkbox(&mut cheats.show_mines, "Show mines");
+        if ui.button("Reveal all").clicked()
+        {
+            cheat = Some(Cheat::RevealAll);
+        }
+        match target
+        {
+            Some(pos) if ui.button(format!("Toggle mine at {}, {}", pos.x, pos.y)).clicked() => cheat = Some(Cheat::ToggleMine(pos)),
+            Some(_) => {}
+            None =>
+            {
+                ui.add_enabled(false, egui::Button::new("Toggle mine"));
+            }
+        }
+        ui.horizontal(|ui|
+        {
+            if ui.button("Win").clicked()
+            {
+                cheat = Some(Cheat::ForceWin);
+            }
+            // without a mine there is nothing to step on, and the game shouldn't count as cheated
+            if ui.button("Lose").clicked() && grid_vis.grid.states.iter().any(|state| state.contains(CellState::Mine))
+            {
+                cheat = Some(Cheat::ForceLoss);
+            }
+        });
+        if ui.button("Regenerate").clicked()
+        {
+            cheat = Some(Cheat::Regenerate);
+        }
+        ui.horizontal(|ui|
+        {
+            if ui.button("Solver step").clicked()
+            {
+                cheat = Some(Cheat::SolverStep);
+            }
+            match cheats.last_step
+            {
+                Some(0) => ui.label("stuck, the next move is a guess"),
+                Some(count) => ui.label(format!("{} deductions", count)),
+                None => ui.label(""),
+            };
+        });
+    });
+
+    let Some(cheat) = cheat else
+    {
+        return;
+    };
+    // nothing cheated goes on the leaderboard
+    interactor.set_assisted();
+    // whatever the solver deduced is stale once the grid is edited behind its back
+    if !matches!(cheat, Cheat::SolverStep)
+    {
+        cheats.forget_solver();
+    }
+    let grid = &mut grid_vis.grid;
+    match cheat
+    {
+        Cheat::RevealAll =>
+        {
+            reveal_all(grid);
+            changes.write(GridChanged::All);
+        }
+        Cheat::ToggleMine(pos) =>
+        {
+            if toggle_mine(grid, pos)
+            {
+                changes.write(GridChanged::All);
+            }
+        }
+        // the interactor only reports the last cell, everything else changed underneath it
+        Cheat::ForceWin =>
+        {
+            force_win(grid, &mut interactor);
+            changes.write(GridChanged::All);
+        }
+        Cheat::ForceLoss => force_loss(grid, &mut interactor),
+        Cheat::Regenerate =>
+        {
+            config.seed = RandomGenerator::hash(config.seed);
+            config.board_file = None;
+            // entering the state it is already in runs the exit and enter systems again
+            next_state.set(AppState::Gameplay);
+        }
+        Cheat::SolverStep =>
+        {
+            let solver = cheats.solver.get_or_insert_with(|| Solver::new(grid));
+            let count = solver_step(grid, &mut interactor, solver);
+            cheats.last_step = Some(count);
+        }
+    }
+}
+
+fn reset(mut cheats: ResMut<Cheats>)
+{
+    cheats.hovered = None;
+    cheats.forget_solver();
+}
+
+pub struct CheatsPlugin;
+
+impl Plugin for CheatsPlugin
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .init_resource::<Cheats>()
+            .add_systems
+            (
+                Update,
+                (
+                    track_pointer.run_if(not(egui_wants_any_pointer_input)),
+                    draw_mines.run_if(|cheats: Res<Cheats>| cheats.show_mines),
+                )
+                .run_if(in_state(AppState::Gameplay))
+            )
+            .add_systems(EguiContextPass, ui.run_if(in_state(AppState::Gameplay)))
+            .add_systems(OnExit(AppState::Gameplay), reset);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn board() -> Grid
+    {
+        let mut grid = Grid::new(4, 4);
+        grid.states[Point::new(0, 0)] = CellState::Mine;
+        grid.states[Point::new(3, 3)] = CellState::NonPlayable;
+        grid.update_adjacency();
+        grid
+    }
+
+    #[test]
+    fn test_toggle_mine_updates_adjacency()
+    {
+        let mut grid = board();
+        assert_eq!(grid.adjacency[Point::new(1, 1)], 1);
+        assert!(toggle_mine(&mut grid, Point::new(2, 2)));
+        assert_eq!(grid.adjacency[Point::new(1, 1)], 2);
+        assert!(toggle_mine(&mut grid, Point::new(0, 0)));
+        assert_eq!(grid.adjacency[Point::new(1, 1)], 1);
+        assert!(!toggle_mine(&mut grid, Point::new(3, 3)));
+    }
+
+    #[test]
+    fn test_force_win_and_loss()
+    {
+        let mut grid = board();
+        let mut interactor = Interactor::new(GameConfig::default().board(), RandomGenerator::new(1));
+        force_win(&mut grid, &mut interactor);
+        assert_eq!(interactor.logic().get_status(), sim::logic::WinStatus::Win);
+
+        let mut grid = board();
+        let mut interactor = Interactor::new(GameConfig::default().board(), RandomGenerator::new(1));
+        force_loss(&mut grid, &mut interactor);
+        assert_eq!(interactor.logic().get_status(), sim::logic::WinStatus::Loss);
+    }
+}
@@ -1,7 +1,16 @@
 
 use crate::interactor::Interactor;
 
+use base::point::Point;
+use sim::grid::CellState;
+use sim::replay::Action;
+use sim::solver::Deduction;
+use sim::solver::Solver;
+use sup::actions::Actions;
+use sup::actions::InputAction;
+use sup::actions::InputBinding;
 use vis::board_vis_tuning::BoardVisTuning;
+use vis::grid_entities::CellPreview;
 use vis::grid_entities::GridVis;
 
 use bevy::input::mouse::MouseWheel;
@@ -11,42 +20,111 @@ use bevy::window::PrimaryWindow;
 #[derive(Debug, Clone, Component)]
 pub struct GameplayCamera;
 
+#[derive(Debug, Clone, Component)]
+pub struct CameraController
+{
+    // world units per second left over from the last pan, decays by friction
+    pub velocity: Vec2,
+    pub friction: f32,
+    // fraction of the scale removed per scroll line, trackpads scroll in pixels instead
+    pub zoom_speed: f32,
+    pub pixels_per_line: f32,
+    pub min_scale: f32,
+    pub max_scale: f32,
+    // how much of the window the board fills after a fit
+    pub fit_fill: f32,
+}
+
+impl Default for CameraController
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            velocity: Vec2::ZERO,
+            friction: 6.0,
+            zoom_speed: 0.1,
+            pixels_per_line: 20.0,
+            min_scale: 0.01,
+            max_scale: 5.0,
+            fit_fill: 0.9,
+        }
+    }
+}
+
+impl CameraController
+{
+    pub fn board_rect(grid_vis: &GridVis, vis_tuning: &BoardVisTuning) -> Rect
+    {
+        Rect::from_corners(Vec2::ZERO, grid_vis.grid.size().as_vec2() * vis_tuning.cell_size)
+    }
+
+    fn fit_scale(&self, window_size: Vec2, board: Rect) -> f32
+    {
+        (board.size() / window_size.max(Vec2::ONE)).max_element() / self.fit_fill
+    }
+
+    // big boards can zoom out past max_scale, at least far enough to see all of them
+    pub fn clamp_scale(&self, scale: f32, window_size: Vec2, board: Rect) -> f32
+    {
+        scale.clamp(self.min_scale, self.max_scale.max(self.fit_scale(window_size, board)))
+    }
+
+    // centers the board and zooms out until all of it is in the window
+    pub fn fit(&mut self, transform: &mut Transform, projection: &mut OrthographicProjection, window_size: Vec2, board: Rect)
+    {
+        projection.scale = self.fit_scale(window_size, board);
+        transform.translation = board.center().extend(transform.translation.z);
+        self.velocity = Vec2::ZERO;
+    }
+}
+
 pub fn camera_pan
 (
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
-    camera_query: Single<(&mut Transform, &mut Projection), (With<Camera2d>, With<GameplayCamera>)>,
+    time: Res<Time>,
+    actions: Actions,
+    camera_query: Single<(&mut Transform, &Projection, &mut CameraController), With<GameplayCamera>>,
     mut previous_mouse_position: Local<Option<Vec2>>,
     window: Single<&Window, With<PrimaryWindow>>,
 )
 {
-    let (mut camera_transform, mut projection_query) = camera_query.into_inner();
-    let Projection::Orthographic(ref mut projection) = *projection_query else
-    {
-        return;
-    };
-
-    let Some(current_mouse_pos) = window.cursor_position() else
+    let (mut camera_transform, projection, mut controller) = camera_query.into_inner();
+    let Projection::Orthographic(projection) = projection else
     {
         return;
     };
 
-    if mouse_buttons.pressed(MouseButton::Right)
+    let dt = time.delta_secs();
+    if let (true, Some(current_mouse_pos)) = (actions.pressed(InputAction::Pan), window.cursor_position())
     {
         if let Some(previous_mouse_pos) = *previous_mouse_position
         {
             let delta = current_mouse_pos - previous_mouse_pos;
-            camera_transform.translation.x -= delta.x * projection.scale;
-            camera_transform.translation.y += delta.y * projection.scale; // Y is inverted in screen space
+            let world_delta = Vec2::new(-delta.x, delta.y) * projection.scale; // Y is inverted in screen space
+            camera_transform.translation += world_delta.extend(0.0);
+            if dt > 0.0
+            {
+                controller.velocity = world_delta / dt;
+            }
         }
 
         *previous_mouse_position = Some(current_mouse_pos);
+        return;
     }
-    else
+
+    // coast after letting go
+    *previous_mouse_position = None;
+    camera_transform.translation += (controller.velocity * dt).extend(0.0);
+    let decay = (-controller.friction * dt).exp();
+    controller.velocity *= decay;
+    if controller.velocity.length_squared() < 1.0
     {
-        *previous_mouse_position = None;
+        controller.velocity = Vec2::ZERO;
     }
 }
 
+// reveals and chords go off on release over the same cell, flags are placed on press
+#[allow(clippy::too_many_arguments)]
 pub fn reveal_cell
 (
     camera_query: Single<(&Camera, &GlobalTransform), (With<Camera2d>, With<GameplayCamera>)>,
@@ -55,64 +133,354 @@ pub fn reveal_cell
     board_vis_tuning: Res<BoardVisTuning>,
     mut interactor: ResMut<Interactor>,
     mut grid_vis: ResMut<GridVis>,
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut cell_preview: ResMut<CellPreview>,
+    actions: Actions,
+    mut pressed: Local<Option<(InputAction, Action)>>,
 )
 {
     let (camera, camera_transform) = camera_query.into_inner();
-    let Some(cursor_position) = window.cursor_position() else {
+    let point = window
+        .cursor_position()
+        .and_then(|cursor_position| camera.viewport_to_world_2d(camera_transform, cursor_position).ok());
+    let hovered = point.and_then(|point| Interactor::world_to_cell(&grid_vis.grid, &board_vis_tuning, &point));
+
+    if let Some(point) = point
+    {
+        gizmos.circle_2d(point, 10., bevy::color::palettes::basic::WHITE);
+    }
+
+    if let Some(pos) = hovered
+    {
+        if actions.just_pressed_pointer(InputAction::Reveal)
+        {
+            *pressed = Some((InputAction::Reveal, Action::Primary(pos)));
+        }
+        else if actions.just_pressed_pointer(InputAction::Chord) && grid_vis.grid.states[pos].contains(CellState::Revealed)
+        {
+            *pressed = Some((InputAction::Chord, Action::Primary(pos)));
+        }
+        else if actions.just_pressed_pointer(InputAction::Flag)
+        {
+            interactor.apply(&mut grid_vis.grid, Action::Secondary(pos));
+        }
+    }
+
+    // a release missed while the pointer was over ui would leave the press stuck
+    if pressed.is_some_and(|(input, _)| !actions.pressed_pointer(input) && !actions.just_released_pointer(input))
+    {
+        *pressed = None;
+    }
+
+    // the press is held until release but only counts while the pressed cell is hovered, so leaving and coming back re-arms it
+    let armed = pressed.filter(|(_, action)| Some(action.pos()) == hovered);
+
+    if let Some((input, _)) = *pressed
+        && actions.just_released_pointer(input)
+    {
+        *pressed = None;
+        if let Some((_, action)) = armed
+        {
+            interactor.apply(&mut grid_vis.grid, action);
+            if let Some(point) = point
+            {
+                gizmos.circle_2d(point, 5., bevy::color::palettes::basic::RED);
+            }
+        }
+    }
+
+    let armed = armed.filter(|_| pressed.is_some());
+    let action = armed.map(|(_, action)| action).or(hovered.map(Action::Primary));
+    cell_preview.preview = action.map(|action| action.preview(interactor.logic(), &grid_vis.grid));
+    cell_preview.pressed = armed.is_some();
+}
+
+// zooms toward the cursor so the point under it stays put
+pub fn camera_zoom
+(
+    camera_query: Single<(&mut Transform, &mut Projection, &CameraController), With<GameplayCamera>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    grid_vis: Res<GridVis>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    mut scroll_events: EventReader<MouseWheel>,
+    actions: Actions,
+)
+{
+    let (mut camera_transform, mut projection_query, controller) = camera_query.into_inner();
+    let Projection::Orthographic(ref mut ortho) = *projection_query else
+    {
         return;
     };
 
-    let Ok(point) = camera.viewport_to_world_2d(camera_transform, cursor_position) else {
+    if !actions.map.is_bound(InputAction::Zoom, InputBinding::MouseWheel)
+    {
+        scroll_events.clear();
+        return;
+    }
+
+    use bevy::input::mouse::MouseScrollUnit;
+    let lines: f32 = scroll_events
+        .read()
+        .map(|event| match event.unit
+        {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / controller.pixels_per_line,
+        })
+        .sum();
+    if lines == 0.0
+    {
+        return;
+    }
+
+    let old_scale = ortho.scale;
+    let board = CameraController::board_rect(&grid_vis, &board_vis_tuning);
+    let new_scale = controller.clamp_scale(old_scale * (-lines * controller.zoom_speed).exp(), window.size(), board);
+    if let Some(cursor_position) = window.cursor_position()
+    {
+        let offset = Vec2::new(cursor_position.x - window.width() / 2.0, window.height() / 2.0 - cursor_position.y) * old_scale;
+        camera_transform.translation += (offset * (1.0 - new_scale / old_scale)).extend(0.0);
+    }
+    ortho.scale = new_scale;
+}
+
+pub fn fit_board
+(
+    window: Single<&Window, With<PrimaryWindow>>,
+    grid_vis: Res<GridVis>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    camera_query: Single<(&mut Transform, &mut Projection, &mut CameraController), With<GameplayCamera>>,
+)
+{
+    let (mut camera_transform, mut projection_query, mut controller) = camera_query.into_inner();
+    let Projection::Orthographic(ref mut ortho) = *projection_query else
+    {
+        return;
+    };
+
+    let board = CameraController::board_rect(&grid_vis, &board_vis_tuning);
+    controller.fit(&mut camera_transform, ortho, window.size(), board);
+}
+
+// the view center stays over the board so it can't be lost off screen
+pub fn camera_clamp
+(
+    grid_vis: Res<GridVis>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    camera_query: Single<(&mut Transform, &mut CameraController), With<GameplayCamera>>,
+)
+{
+    let (mut camera_transform, mut controller) = camera_query.into_inner();
+    let board = CameraController::board_rect(&grid_vis, &board_vis_tuning);
+    let center = camera_transform.translation.truncate();
+    let clamped = center.clamp(board.min, board.max);
+    if clamped == center
+    {
+        return;
+    }
+
+    // stop coasting into the edge
+    controller.velocity *= Vec2::select(clamped.cmpeq(center), Vec2::ONE, Vec2::ZERO);
+    camera_transform.translation = clamped.extend(camera_transform.translation.z);
+}
+
+// the cell picked with keys or a gamepad, hidden again as soon as the mouse moves
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CellCursor
+{
+    pub pos: Option<Point>,
+    pub active: bool,
+}
+
+pub fn move_cursor
+(
+    actions: Actions,
+    mut mouse_motion: EventReader<CursorMoved>,
+    grid_vis: Res<GridVis>,
+    mut cursor: ResMut<CellCursor>,
+)
+{
+    if mouse_motion.read().count() > 0
+    {
+        cursor.active = false;
+    }
+
+    let pressed = |action: InputAction| actions.just_pressed_buttons(action) as i32;
+    let delta = Point::new
+    (
+        pressed(InputAction::CursorRight) - pressed(InputAction::CursorLeft),
+        pressed(InputAction::CursorUp) - pressed(InputAction::CursorDown),
+    );
+    if delta == Point::new(0, 0)
+    {
         return;
+    }
+
+    let size = grid_vis.grid.size();
+    let pos = match cursor.pos
+    {
+        // the first press only shows the cursor
+        Some(pos) if cursor.active => Point::new
+        (
+            (pos.x + delta.x).clamp(0, size.width - 1),
+            (pos.y + delta.y).clamp(0, size.height - 1),
+        ),
+        Some(pos) => pos,
+        None => Point::new(size.width / 2, size.height / 2),
     };
+    cursor.pos = Some(pos);
+    cursor.active = true;
+}
 
-    gizmos.circle_2d(point, 10., bevy::color::palettes::basic::WHITE);
+pub fn cursor_actions
+(
+    actions: Actions,
+    cursor: Res<CellCursor>,
+    mut interactor: ResMut<Interactor>,
+    mut grid_vis: ResMut<GridVis>,
+)
+{
+    let Some(pos) = cursor.pos.filter(|_| cursor.active) else
+    {
+        return;
+    };
 
-    if mouse_buttons.just_pressed(MouseButton::Left)
+    if actions.just_pressed_buttons(InputAction::Reveal)
     {
-        gizmos.circle_2d(point, 5., bevy::color::palettes::basic::RED);
-        interactor.on_primary(&mut grid_vis.grid, &board_vis_tuning, &point);
+        interactor.apply(&mut grid_vis.grid, Action::Primary(pos));
     }
-    else if mouse_buttons.just_pressed(MouseButton::Right)
+    else if actions.just_pressed_buttons(InputAction::Flag)
     {
-        gizmos.circle_2d(point, 5., bevy::color::palettes::basic::BLUE);
-        interactor.on_secondary(&mut grid_vis.grid, &board_vis_tuning, &point);
+        interactor.apply(&mut grid_vis.grid, Action::Secondary(pos));
+    }
+    else if actions.just_pressed_buttons(InputAction::Chord) && grid_vis.grid.states[pos].contains(CellState::Revealed)
+    {
+        interactor.apply(&mut grid_vis.grid, Action::Primary(pos));
     }
 }
 
-pub fn camera_zoom
+pub fn draw_cursor
 (
-    ortho_query: Single<&mut Projection, (With<Camera2d>, With<GameplayCamera>)>,
-    mut scroll_events: EventReader<MouseWheel>,
-    keys: Res<ButtonInput<KeyCode>>,
+    mut gizmos: Gizmos,
+    cursor: Res<CellCursor>,
+    board_vis_tuning: Res<BoardVisTuning>,
 )
 {
-    let Projection::Orthographic(ref mut ortho) = *ortho_query.into_inner() else
+    let Some(pos) = cursor.pos.filter(|_| cursor.active) else
+    {
+        return;
+    };
+
+    let center = (pos.as_vec2() + Vec2::splat(0.5)) * board_vis_tuning.cell_size;
+    gizmos.rect_2d(Isometry2d::from_translation(center), board_vis_tuning.cell_size, bevy::color::palettes::basic::WHITE);
+}
+
+// keeps the cursor away from the screen edges, only moves once it leaves the inner part of the view
+pub fn camera_follow_cursor
+(
+    time: Res<Time>,
+    cursor: Res<CellCursor>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    camera_query: Single<(&mut Transform, &Projection), (With<Camera2d>, With<GameplayCamera>)>,
+)
+{
+    const MARGIN: f32 = 0.7;
+    const SPEED: f32 = 8.0;
+
+    let Some(pos) = cursor.pos.filter(|_| cursor.active) else
     {
         return;
     };
 
-    if keys.just_pressed(KeyCode::KeyR)
+    let (mut camera_transform, projection) = camera_query.into_inner();
+    let Projection::Orthographic(projection) = projection else
     {
-        ortho.scale = 1.0;
+        return;
+    };
+
+    let target = (pos.as_vec2() + Vec2::splat(0.5)) * board_vis_tuning.cell_size;
+    let camera = camera_transform.translation.truncate();
+    let limit = projection.area.half_size() * MARGIN;
+    let offset = target - camera;
+    let overshoot = offset - offset.clamp(-limit, limit);
+    if overshoot == Vec2::ZERO
+    {
+        return;
     }
 
-    use bevy::input::mouse::MouseScrollUnit;
-    for event in scroll_events.read()
+    let t = 1.0 - (-SPEED * time.delta_secs()).exp();
+    camera_transform.translation += (overshoot * t).extend(0.0);
+}
+
+pub fn undo
+(
+    actions: Actions,
+    mut interactor: ResMut<Interactor>,
+    mut grid_vis: ResMut<GridVis>,
+)
+{
+    if actions.just_pressed(InputAction::Undo)
     {
-        match event.unit
-        {
-            MouseScrollUnit::Line =>
-            {
-                ortho.scale -= event.y * 0.1 * ortho.scale;
-            },
-            MouseScrollUnit::Pixel =>
-            {
-                println!("Scroll (pixel units): vertical: {}, horizontal: {}", event.y, event.x);
-            }
-        }
+        interactor.undo(&mut grid_vis.grid);
     }
-    ortho.scale = ortho.scale.clamp(0.01, 5.0);
 }
 
+// a cell the solver can prove from what is revealed, shown for a few seconds
+#[derive(Resource, Debug, Clone)]
+pub struct Hint
+{
+    pub deduction: Deduction,
+    pub timer: Timer,
+}
+
+pub fn hint
+(
+    mut commands: Commands,
+    actions: Actions,
+    mut interactor: ResMut<Interactor>,
+    grid_vis: Res<GridVis>,
+)
+{
+    if !actions.just_pressed(InputAction::Hint)
+    {
+        return;
+    }
+
+    let grid = &grid_vis.grid;
+    let deductions = Solver::new(grid).step(grid);
+    // a hidden mine is only worth pointing at if it isn't flagged yet
+    let useful = deductions.into_iter().find(|deduction| match deduction
+    {
+        Deduction::Safe(_) => true,
+        Deduction::Mine(pos) => !grid.states[*pos].contains(CellState::Flag),
+    });
+
+    // nothing is shown when the next move is a guess
+    if let Some(deduction) = useful
+    {
+        interactor.set_assisted();
+        commands.insert_resource(Hint{ deduction, timer: Timer::from_seconds(3.0, TimerMode::Once) });
+    }
+}
+
+pub fn draw_hint
+(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut gizmos: Gizmos,
+    board_vis_tuning: Res<BoardVisTuning>,
+    mut hint: ResMut<Hint>,
+)
+{
+    if hint.timer.tick(time.delta()).finished()
+    {
+        commands.remove_resource::<Hint>();
+        return;
+    }
+
+    let (pos, color) = match hint.deduction
+    {
+        Deduction::Safe(pos) => (pos, bevy::color::palettes::basic::GREEN),
+        Deduction::Mine(pos) => (pos, bevy::color::palettes::basic::RED),
+    };
+    let center = (pos.as_vec2() + Vec2::splat(0.5)) * board_vis_tuning.cell_size;
+    gizmos.circle_2d(center, board_vis_tuning.cell_size.min_element() * 0.4, color);
+}
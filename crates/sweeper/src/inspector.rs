@@ -0,0 +1,292 @@
+use base::tuning::Tuning;
+use bevyx::tuning::TuningErrors;
+
+use bevy::prelude::*;
+use bevy::reflect::DynamicEnum;
+use bevy::reflect::DynamicVariant;
+use bevy::reflect::ReflectMut;
+use bevy::reflect::ReflectRef;
+use bevy::reflect::TypeInfo;
+use bevy::reflect::TypePath;
+use bevy::reflect::VariantInfo;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContext;
+use bevy_egui::egui;
+
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+// variant names of an enum with no fields, those get a combo box
+fn unit_variants(value: &dyn PartialReflect) -> Option<Vec<&'static str>>
+{
+    let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() else
+    {
+        return None;
+    };
+    info.iter()
+        .map(|variant| matches!(variant, VariantInfo::Unit(_)).then(|| variant.name()))
+        .collect()
+}
+
+// values that fit on one line next to their name
+fn is_inline(value: &dyn PartialReflect) -> bool
+{
+    value.try_downcast_ref::<Srgba>().is_some()
+        || matches!(value.reflect_ref(), ReflectRef::Opaque(_))
+        || unit_variants(value).is_some()
+}
+
+fn edit_opaque(ui: &mut egui::Ui, value: &mut dyn PartialReflect) -> bool
+{
+    if let Some(value) = value.try_downcast_mut::<f32>()
+    {
+        let speed = (*value * 0.01).abs().max(0.001);
+        return ui.add(egui::DragValue::new(value).speed(speed)).changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<u32>()
+    {
+        return ui.add(egui::DragValue::new(value)).changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<u64>()
+    {
+        return ui.add(egui::DragValue::new(value)).changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<i32>()
+    {
+        return ui.add(egui::DragValue::new(value)).changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<usize>()
+    {
+        return ui.add(egui::DragValue::new(value)).changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<bool>()
+    {
+        return ui.checkbox(value, "").changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<String>()
+    {
+        return ui.text_edit_singleline(value).changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<PathBuf>()
+    {
+        let mut text = value.to_string_lossy().into_owned();
+        let changed = ui.text_edit_singleline(&mut text).changed();
+        if changed
+        {
+            *value = text.into();
+        }
+        return changed;
+    }
+
+    ui.weak(value.reflect_short_type_path());
+    false
+}
+
+fn edit_unit_enum(ui: &mut egui::Ui, value: &mut dyn PartialReflect, variants: &[&'static str]) -> bool
+{
+    let ReflectRef::Enum(current) = value.reflect_ref() else
+    {
+        return false;
+    };
+    let current = current.variant_name().to_owned();
+
+    let mut selected = current.as_str();
+    egui::ComboBox::from_id_salt(ui.id())
+        .selected_text(selected)
+        .show_ui(ui, |ui|
+        {
+            for variant in variants
+            {
+                ui.selectable_value(&mut selected, variant, *variant);
+            }
+        });
+
+    selected != current && value.try_apply(&DynamicEnum::new(selected, DynamicVariant::Unit)).is_ok()
+}
+
+fn edit_field(ui: &mut egui::Ui, name: &str, value: &mut dyn PartialReflect) -> bool
+{
+    if is_inline(value)
+    {
+        return ui.horizontal(|ui|
+        {
+            ui.label(name);
+            edit(ui, value)
+        }).inner;
+    }
+
+    egui::CollapsingHeader::new(name)
+        .default_open(true)
+        .show(ui, |ui| edit(ui, value))
+        .body_returned
+        .unwrap_or(false)
+}
+
+// draws an editor for any reflected value, true when something was changed
+fn edit(ui: &mut egui::Ui, value: &mut dyn PartialReflect) -> bool
+{
+    if let Some(color) = value.try_downcast_mut::<Srgba>()
+    {
+        let mut rgba = color.to_f32_array();
+        let changed = ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed();
+        if changed
+        {
+            *color = Srgba::from_f32_array(rgba);
+        }
+        return changed;
+    }
+    if let Some(variants) = unit_variants(value)
+    {
+        return edit_unit_enum(ui, value, &variants);
+    }
+
+    let mut changed = false;
+    match value.reflect_mut()
+    {
+        ReflectMut::Struct(value) =>
+        {
+            for index in 0..value.field_len()
+            {
+                let name = value.name_at(index).unwrap_or_default().to_owned();
+                if let Some(field) = value.field_at_mut(index)
+                {
+                    changed |= edit_field(ui, &name, field);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) =>
+        {
+            for index in 0..value.field_len()
+            {
+                if let Some(field) = value.field_mut(index)
+                {
+                    changed |= edit_field(ui, &index.to_string(), field);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) =>
+        {
+            for index in 0..value.field_len()
+            {
+                if let Some(field) = value.field_mut(index)
+                {
+                    changed |= edit_field(ui, &index.to_string(), field);
+                }
+            }
+        }
+        ReflectMut::Array(value) =>
+        {
+            for index in 0..value.len()
+            {
+                if let Some(item) = value.get_mut(index)
+                {
+                    changed |= edit_field(ui, &index.to_string(), item);
+                }
+            }
+        }
+        ReflectMut::List(value) =>
+        {
+            for index in 0..value.len()
+            {
+                if let Some(item) = value.get_mut(index)
+                {
+                    changed |= edit_field(ui, &index.to_string(), item);
+                }
+            }
+        }
+        // only the fields of the current variant, switching needs the variant's whole contents
+        ReflectMut::Enum(value) =>
+        {
+            ui.label(value.variant_name());
+            for index in 0..value.field_len()
+            {
+                let name = value.name_at(index).map(str::to_owned).unwrap_or_else(|| index.to_string());
+                if let Some(field) = value.field_at_mut(index)
+                {
+                    changed |= edit_field(ui, &name, field);
+                }
+            }
+        }
+        ReflectMut::Opaque(value) =>
+        {
+            changed |= edit_opaque(ui, value);
+        }
+        _ =>
+        {
+            ui.weak("not editable");
+        }
+    }
+    changed
+}
+
+fn inspect<T>
+(
+    mut tuning: ResMut<T>,
+    mut errors: ResMut<TuningErrors>,
+    dev_window: Option<Single<(&mut EguiContext, &Window), Without<PrimaryWindow>>>,
+)
+    where T: Tuning + Resource + Reflect + TypePath + Default + serde::Serialize + for<'de> serde::Deserialize<'de>
+{
+    let Some((mut ctx, window)) = dev_window.map(Single::into_inner) else
+    {
+        return;
+    };
+    if !window.visible
+    {
+        return;
+    }
+
+    egui::Window::new(T::short_type_path()).show(ctx.get_mut(), |ui|
+    {
+        ui.horizontal(|ui|
+        {
+            if ui.button("Save").clicked()
+            {
+                tuning.save();
+            }
+            if ui.button("Revert").clicked()
+            {
+                match T::try_load()
+                {
+                    Ok(saved) => *tuning = saved,
+                    Err(err) =>
+                    {
+                        errors.errors.insert(T::short_type_path(), format!("{}: {}", T::path().display(), err));
+                    }
+                }
+            }
+        });
+        ui.separator();
+
+        // looking doesn't count as a change, only edits do
+        egui::ScrollArea::vertical().show(ui, |ui|
+        {
+            if edit(ui, tuning.bypass_change_detection().as_partial_reflect_mut())
+            {
+                tuning.set_changed();
+            }
+        });
+    });
+}
+
+// a window in the dev window that edits the resource live, saves it to its RON file or reverts to it
+pub struct InspectorPlugin<T>(PhantomData<T>);
+
+impl<T> Default for InspectorPlugin<T>
+{
+    fn default() -> Self
+    {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Plugin for InspectorPlugin<T>
+    where T: Tuning + Resource + Reflect + TypePath + Default + serde::Serialize + for<'de> serde::Deserialize<'de>
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .init_resource::<TuningErrors>()
+            .add_systems(Update, inspect::<T>.run_if(resource_exists::<T>));
+    }
+}
@@ -1,9 +1,13 @@
-
 use sim::grid::Grid;
+use sim::grid_gen::BoardConfig;
+use sim::logic::ActionResult;
 use sim::logic::Logic;
-use sim::logic::LogicPreview;
-use sim::logic::PreviewKind;
+use sim::replay::Action;
+use sim::replay::Replay;
 use vis::board_vis_tuning::BoardVisTuning;
+use vis::animations::BoardEffect;
+use vis::grid_entities::GridChanged;
+use base::point::Point;
 use base::random::RandomGenerator;
 
 use bevy::prelude::*;
@@ -12,15 +16,43 @@ use bevy::prelude::*;
 pub struct Interactor
 {
     logic: Logic,
+    new_logic: fn() -> Logic,
+    rand: RandomGenerator,
+    replay: Replay,
+    // the board and generator before the first action, undo replays from here
+    start: Option<(Grid, RandomGenerator)>,
+    assisted: bool,
+    // what the grid went through since the last send_changes
+    changes: Vec<GridChanged>,
+    effects: Vec<BoardEffect>,
+    // the last action the logic refused, the grid is as it was before it
+    error: Option<sim::Error>,
+    // something the player should be told, shown in the hud
+    notice: Option<String>,
 }
 
 impl Interactor
 {
-    pub fn new() -> Self
+    // rand must be the generator the board was built with, so replays make the same first guess
+    pub fn new(board: BoardConfig, rand: RandomGenerator) -> Self
+    {
+        Self::with_logic(board, rand, Logic::new)
+    }
+
+    pub fn with_logic(board: BoardConfig, rand: RandomGenerator, new_logic: fn() -> Logic) -> Self
     {
         Interactor
         {
-            logic: Logic::new(),
+            logic: new_logic(),
+            new_logic,
+            rand,
+            replay: Replay::new(board),
+            start: None,
+            assisted: false,
+            changes: Vec::new(),
+            effects: Vec::new(),
+            error: None,
+            notice: None,
         }
     }
 
@@ -29,54 +61,155 @@ impl Interactor
         &self.logic
     }
 
-    pub fn on_primary(&mut self, grid: &mut Grid, vis_tuning: &BoardVisTuning, world_pos: &Vec2)
+    pub fn replay(&self) -> &Replay
     {
-        let grid_world_size = grid.size().as_vec2() * vis_tuning.cell_size;
-        if world_pos.cmplt(Vec2::new(0.0, 0.0)).any() || world_pos.cmpge(grid_world_size).any()
-        {
-            return;
-        }
-        let pos = world_pos / vis_tuning.cell_size;
-        let pos = pos.as_ivec2();
-        let preview = self.logic.preview_guess(grid, pos.into());
-        self.actualize_preview(grid, &preview);
+        &self.replay
     }
 
-    pub fn on_secondary(&mut self, grid: &mut Grid, vis_tuning: &BoardVisTuning, world_pos: &Vec2)
+    // true once an undo or a hint was used, these games don't go on the leaderboard
+    pub fn assisted(&self) -> bool
     {
-        let grid_world_size = grid.size().as_vec2() * vis_tuning.cell_size;
-        if world_pos.cmplt(Vec2::new(0.0, 0.0)).any() || world_pos.cmpge(grid_world_size).any()
-        {
-            return;
-        }
-        let pos = world_pos / vis_tuning.cell_size;
-        let pos = pos.as_ivec2();
-        let preview = self.logic.preview_flag(grid, pos.into());
-        self.actualize_preview(grid, &preview);
+        self.assisted
     }
 
-    fn actualize_preview(&mut self, grid: &mut Grid, preview: &LogicPreview)
+    pub fn set_assisted(&mut self)
     {
-        match preview.kind
+        self.assisted = true;
+    }
+
+    pub fn submit(&mut self, grid: &Grid)
+    {
+        self.logic.submit(grid);
+    }
+
+    pub fn apply(&mut self, grid: &mut Grid, action: Action)
+    {
+        if self.start.is_none()
         {
-            PreviewKind::FirstGuess =>
-            {
-                let mut rand = RandomGenerator::new(2);
-                self.logic.do_first_guess(grid, &mut rand, preview);
-            }
-            PreviewKind::Guess =>
+            self.start = Some((grid.clone(), self.rand.clone()));
+        }
+        match self.replay.apply(&mut self.logic, grid, &mut self.rand, action)
+        {
+            Ok(Some(result)) =>
             {
-                self.logic.do_guess(grid, preview);
+                self.changes.extend(GridChanged::from_result(&result));
+                if let ActionResult::Guess(guess) = &result
+                    && guess.exploded
+                {
+                    self.effects.push(BoardEffect::Explosion(guess.pos));
+                }
             }
-            PreviewKind::Flag =>
+            Ok(None) => {}
+            // the board is too crowded to clear the first guess, it's played without the safety net instead
+            Err(err @ sim::Error::NoRoomForMines{ .. }) =>
             {
-                self.logic.do_flag(grid, preview);
+                grid.first_guess = false;
+                // undo replays from the start, it has to skip the safety net too
+                if let Some((start, _)) = &mut self.start
+                {
+                    start.first_guess = false;
+                }
+                // a replay would hit the same wall, so this game can't be verified
+                self.assisted = true;
+                self.notice = Some(format!("{err}, the first guess is no longer safe"));
+                self.apply(grid, action);
             }
-            PreviewKind::Chord =>
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    pub fn take_error(&mut self) -> Option<sim::Error>
+    {
+        self.error.take()
+    }
+
+    pub fn take_notice(&mut self) -> Option<String>
+    {
+        self.notice.take()
+    }
+
+    // rebuilds the game from the start without the last action, returns false if there was nothing to undo
+    pub fn undo(&mut self, grid: &mut Grid) -> bool
+    {
+        let Some((start, rand)) = &self.start else
+        {
+            return false;
+        };
+
+        let mut actions = self.replay.actions.clone();
+        if actions.pop().is_none()
+        {
+            return false;
+        }
+
+        *grid = start.clone();
+        self.rand = rand.clone();
+        self.logic = (self.new_logic)();
+        self.replay = Replay::new(self.replay.board.clone());
+        for action in actions
+        {
+            if let Err(err) = self.replay.apply(&mut self.logic, grid, &mut self.rand, action)
             {
-                self.logic.do_chord(grid, preview);
+                self.error = Some(err);
             }
         }
+        self.assisted = true;
+        self.changes.push(GridChanged::All);
+        true
+    }
+
+    pub fn send_changes
+    (
+        mut interactor: ResMut<Interactor>,
+        mut changes: EventWriter<GridChanged>,
+        mut effects: EventWriter<BoardEffect>,
+    )
+    {
+        changes.write_batch(interactor.changes.drain(..));
+        effects.write_batch(interactor.effects.drain(..));
+    }
+
+    pub fn world_to_cell(grid: &Grid, vis_tuning: &BoardVisTuning, world_pos: &Vec2) -> Option<Point>
+    {
+        let grid_world_size = grid.size().as_vec2() * vis_tuning.cell_size;
+        if world_pos.cmplt(Vec2::new(0.0, 0.0)).any() || world_pos.cmpge(grid_world_size).any()
+        {
+            return None;
+        }
+        let pos = world_pos / vis_tuning.cell_size;
+        Some(pos.as_ivec2().into())
     }
 }
 
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use sup::config::GameConfig;
+
+    #[test]
+    fn test_crowded_first_guess()
+    {
+        // every free cell is next to the guess, so its mines have nowhere to go
+        let mut grid = sim::board_format::from_ascii("\
+**.
+*..
+...
+").unwrap();
+        grid.first_guess = true;
+        let mut interactor = Interactor::new(GameConfig::default().board(), RandomGenerator::new(1));
+        interactor.apply(&mut grid, Action::Primary(Point::new(1, 1)));
+
+        assert!(grid.states[Point::new(1, 1)].contains(sim::grid::CellState::Revealed));
+        assert!(interactor.take_error().is_none());
+        assert!(interactor.take_notice().is_some());
+        assert!(interactor.assisted());
+
+        // undo goes back to a start that doesn't try the safe first guess again
+        assert!(interactor.undo(&mut grid));
+        assert!(!grid.first_guess);
+        interactor.apply(&mut grid, Action::Primary(Point::new(1, 1)));
+        assert!(interactor.take_notice().is_none());
+        assert!(grid.states[Point::new(1, 1)].contains(sim::grid::CellState::Revealed));
+    }
+}
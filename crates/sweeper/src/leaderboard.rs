@@ -0,0 +1,92 @@
+use base::debug_name;
+use base::save::SaveData;
+use sim::grid_gen::BoardConfig;
+use sim::replay::Replay;
+
+use bevy::prelude::*;
+
+const MAX_ENTRIES_PER_BOARD: usize = 10;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LeaderboardEntry
+{
+    pub moves: u32,
+    pub replay: Replay,
+}
+
+// local high scores, every entry is re-simulated before it is trusted
+#[derive(Debug, Clone, Default, Resource, serde::Serialize, serde::Deserialize)]
+pub struct Leaderboard
+{
+    entries: Vec<LeaderboardEntry>,
+}
+
+impl SaveData for Leaderboard
+{
+    fn path() -> &'static std::path::Path
+    {
+        std::path::Path::new("save/leaderboard.ron")
+    }
+}
+
+impl Leaderboard
+{
+    // drops any entry whose replay no longer reaches the claimed result
+    pub fn load_verified() -> Self
+    {
+        let mut leaderboard = Self::load();
+        leaderboard.entries.retain(|entry|
+        {
+            let valid = entry.replay.verify(entry.moves);
+            if !valid
+            {
+                eprintln!("{} -- Dropping leaderboard entry that failed verification {:?}", debug_name!(), entry.replay.board);
+            }
+            valid
+        });
+        leaderboard
+    }
+
+    // returns true if the entry verified and made it onto the table
+    pub fn submit(&mut self, entry: LeaderboardEntry) -> bool
+    {
+        let rank = self.entries_for(&entry.replay.board)
+            .take_while(|other| other.moves <= entry.moves)
+            .count();
+        if rank >= MAX_ENTRIES_PER_BOARD || !entry.replay.verify(entry.moves)
+        {
+            return false;
+        }
+
+        let board = entry.replay.board.clone();
+        let insert_at = self.entries
+            .iter()
+            .position(|other| other.replay.board.same_rules(&board) && other.moves > entry.moves)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(insert_at, entry);
+
+        let mut count = 0;
+        self.entries.retain(|other|
+        {
+            if !other.replay.board.same_rules(&board)
+            {
+                return true;
+            }
+            count += 1;
+            count <= MAX_ENTRIES_PER_BOARD
+        });
+        true
+    }
+
+    // best first
+    pub fn entries_for<'a>(&'a self, board: &BoardConfig) -> impl Iterator<Item = &'a LeaderboardEntry> + use<'a>
+    {
+        let board = board.clone();
+        self.entries.iter().filter(move |entry| entry.replay.board.same_rules(&board))
+    }
+
+    pub fn best(&self, board: &BoardConfig) -> Option<&LeaderboardEntry>
+    {
+        self.entries_for(board).next()
+    }
+}
@@ -1,12 +1,22 @@
 
+mod daily;
 mod debug;
 mod input;
+mod inspector;
 mod layers;
 mod screens;
 mod app_state;
 mod interactor;
+mod leaderboard;
+mod minimap;
+mod puzzle;
+mod stats;
+mod touch;
 use crate::input::GameplayCamera;
 
+use base::save::SaveData;
+use base::tuning::Tuning;
+
 use bevy::dev_tools::fps_overlay::FpsOverlayConfig;
 use bevy::dev_tools::fps_overlay::FpsOverlayPlugin;
 use bevy::prelude::*;
@@ -31,6 +41,7 @@ fn setup
             ..default()
         },
         GameplayCamera,
+        input::CameraController::default(),
         UiSourceCamera::<{ layers::GAME_LAYER }>,
     ));
     commands.spawn
@@ -111,13 +122,20 @@ fn main()
         .add_plugins(UiLunexPlugin::<{ layers::UI_LAYER }> )
         .add_plugins(UiLunexDebugPlugin::<{ layers::DEBUG_LAYER_2D }, { layers::DEBUG_LAYER_3D }>)
         .insert_state(crate::app_state::AppState::Splash)
+        .insert_resource(leaderboard::Leaderboard::load_verified())
+        .insert_resource(stats::Stats::load())
+        .insert_resource(sup::actions::InputMap::load())
         .add_plugins(crate::debug::DebugPlugin)
         .add_plugins(EguiPlugin { enable_multipass_for_primary_context: true })
         .add_plugins(vis::GameVisPlugin)
         .add_plugins(app_state::splash::SplashAppState)
         .add_plugins(app_state::gameplay::GameplayAppState)
         .add_plugins(app_state::frontend::FrontendAppState)
+        .add_plugins(app_state::editor::EditorAppState)
+        .add_plugins(puzzle::PuzzlePlugin)
+        .add_plugins(touch::TouchPlugin)
         .add_plugins(screens::custom::CustomScreen)
+        .add_plugins(screens::settings::SettingsScreen)
         .add_systems(Startup, setup)
         .insert_resource(UiDebugOptions
         {
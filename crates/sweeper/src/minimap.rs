@@ -0,0 +1,73 @@
+use crate::input::CameraController;
+use crate::input::GameplayCamera;
+
+use vis::board_vis_tuning::BoardVisTuning;
+use vis::minimap::Minimap;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContext;
+use bevy_egui::EguiUserTextures;
+use bevy_egui::egui;
+
+// on screen length of the longest side
+const SIZE: f32 = 200.0;
+
+// the board in the corner with the camera view outlined, clicking or dragging on it moves the view there
+pub fn ui
+(
+    mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+    mut user_textures: ResMut<EguiUserTextures>,
+    minimap: Res<Minimap>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    camera_query: Single<(&mut Transform, &Projection, &mut CameraController), With<GameplayCamera>>,
+)
+{
+    let Ok(mut ctx) = egui_ctx.single_mut() else
+    {
+        return;
+    };
+
+    let (mut camera_transform, projection, mut controller) = camera_query.into_inner();
+    let Projection::Orthographic(projection) = projection else
+    {
+        return;
+    };
+
+    // weak, egui drops the texture by itself once the image asset is removed
+    let texture = user_textures.add_image(minimap.image.clone_weak());
+    let pixels = minimap.pixel_size().as_vec2();
+    let display_size = pixels * (SIZE / pixels.max_element());
+    // the last row and column of pixels can reach past the board
+    let world_size = pixels * minimap.cells_per_pixel as f32 * board_vis_tuning.cell_size;
+
+    egui::Area::new(egui::Id::new("minimap"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+        .show(ctx.get_mut(), |ui|
+        {
+            let image = egui::Image::new(egui::load::SizedTexture::new(texture, egui::vec2(display_size.x, display_size.y)))
+                .sense(egui::Sense::click_and_drag());
+            let response = ui.add(image);
+            let rect = response.rect;
+
+            let to_screen = |world: Vec2|
+            {
+                let uv = world / world_size;
+                rect.left_bottom() + egui::vec2(uv.x * rect.width(), -uv.y * rect.height())
+            };
+            let center = camera_transform.translation.truncate();
+            let view = egui::Rect::from_two_pos(to_screen(center + projection.area.min), to_screen(center + projection.area.max));
+            ui.painter()
+                .with_clip_rect(rect)
+                .rect_stroke(view, 0.0, egui::Stroke::new(1.0, egui::Color32::WHITE), egui::StrokeKind::Inside);
+
+            if (response.clicked() || response.dragged())
+                && let Some(pointer) = response.interact_pointer_pos()
+            {
+                let uv = (pointer - rect.left_bottom()) / rect.size();
+                let world = Vec2::new(uv.x, -uv.y) * world_size;
+                camera_transform.translation = world.extend(camera_transform.translation.z);
+                controller.velocity = Vec2::ZERO;
+            }
+        });
+}
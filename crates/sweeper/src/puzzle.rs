@@ -0,0 +1,60 @@
+use crate::interactor::Interactor;
+
+use bevyx::ron::RonAssetPlugin;
+use sim::grid::Grid;
+use sim::puzzle::PuzzlePack;
+use sup::actions::Actions;
+use sup::actions::InputAction;
+use vis::grid_entities::GridVis;
+
+use bevy::prelude::*;
+
+#[derive(Asset, TypePath, Debug, serde::Deserialize)]
+#[serde(transparent)]
+pub struct PuzzlePackAsset
+{
+    pub pack: PuzzlePack,
+}
+
+#[derive(Resource, Debug)]
+pub struct PuzzlePackHandle(pub Handle<PuzzlePackAsset>);
+
+// present while the current game is a level from the puzzle pack
+#[derive(Resource, Debug, Clone)]
+pub struct ActivePuzzle
+{
+    pub name: String,
+    pub grid: Grid,
+}
+
+fn load_pack(mut commands: Commands, asset_server: Res<AssetServer>)
+{
+    commands.insert_resource(PuzzlePackHandle(asset_server.load("puzzles/pack.puzzles.ron")));
+}
+
+// flags are only checked once the player says they are done
+pub fn submit
+(
+    actions: Actions,
+    mut interactor: ResMut<Interactor>,
+    grid_vis: Res<GridVis>,
+)
+{
+    if actions.just_pressed(InputAction::Submit)
+    {
+        interactor.submit(&grid_vis.grid);
+    }
+}
+
+pub struct PuzzlePlugin;
+
+impl Plugin for PuzzlePlugin
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .add_plugins(RonAssetPlugin::<PuzzlePackAsset>::new(&["puzzles.ron"]))
+            .add_systems(Startup, load_pack)
+            ;
+    }
+}
@@ -2,6 +2,7 @@ pub mod home;
 pub mod hud;
 pub mod splash;
 pub mod eog;
+pub mod settings;
 
 // pub mod custom_logic;
 pub mod custom;
@@ -1,9 +1,10 @@
 use crate::app_state::AppState;
 use crate::layers;
 use crate::screens;
-use crate::app_state::gameplay::GameConfig;
 use crate::app_state::frontend::ScreenUpdate;
 
+use sup::config::GameConfig;
+
 use bevy::prelude::*;
 use strum::EnumIter;
 
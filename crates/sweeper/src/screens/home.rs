@@ -1,6 +1,18 @@
 use crate::app_state::AppState;
+use crate::app_state::gameplay::ReplayPlayback;
+use crate::daily;
+use crate::daily::DailyChallenge;
 use crate::layers;
+use crate::leaderboard::Leaderboard;
+use crate::puzzle::ActivePuzzle;
+use crate::puzzle::PuzzlePackAsset;
+use crate::puzzle::PuzzlePackHandle;
 use crate::screens;
+use crate::screens::hud::HudMessage;
+use crate::screens::settings::SettingsScreenState;
+use crate::stats::Stats;
+
+use sup::config::GameConfig;
 
 use bevy::prelude::*;
 use lunex::*;
@@ -9,11 +21,19 @@ use strum::EnumIter;
 #[derive(Component)]
 pub struct HomeScreen;
 
+// why a button did nothing, e.g. the puzzle pack failed to load
+#[derive(Component)]
+struct HomeMessage;
+
 #[derive(Debug, Eq, PartialEq, EnumIter, strum::Display)]
 enum Buttons
 {
     Play,
+    Daily,
+    Puzzle,
     Custom,
+    Replay,
+    Editor,
     Settings,
     Credits,
     Quit,
@@ -39,6 +59,28 @@ pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>)
             HomeScreen,
         ));
 
+        ui.spawn
+        ((
+            Name::new("Message"),
+            UiLayout::window().pos(Rl((5.0, 90.0))).size(Rl((90.0, 6.0))).pack(),
+            layers::UI_RENDER_LAYER,
+            HomeScreen,
+            Pickable::IGNORE,
+        ))
+        .with_children(|ui|
+        {
+            ui.spawn
+            ((
+                Name::new("Message Text"),
+                UiColor::from(Color::srgb(1.0, 0.0, 0.0)),
+                Text2d::default(),
+                layers::UI_RENDER_LAYER,
+                HomeScreen,
+                HomeMessage,
+                Pickable::IGNORE,
+            ));
+        });
+
         ui.spawn
         ((
             Name::new("ButtonContainer"),
@@ -99,6 +141,82 @@ pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>)
                     next.set(AppState::Gameplay);
                 });
         
+            ui.spawn(make_button(Buttons::Daily))
+                .with_children(|ui|
+                {
+                    ui.spawn(make_button_child(Buttons::Daily));
+                })
+                .observe(
+                |
+                     _: Trigger<Pointer<Click>>,
+                     mut next: ResMut<NextState<AppState>>,
+                     screen: Option<Single<Entity, (With<HomeScreen>, With<UiLayoutRoot>)>>,
+                     mut config: ResMut<GameConfig>,
+                     stats: Res<Stats>,
+                     mut cmd: Commands,
+                |
+                {
+                    let day = daily::today();
+                    let (year, month, date) = daily::civil_date(day);
+                    println!("daily {year}-{month:02}-{date:02} [{}], best {:?}", sim::daily::rules(day).name, stats.daily.get(&day));
+                    config.set_board(&sim::daily::board(day));
+                    cmd.insert_resource(DailyChallenge{ day });
+                    if let Some(entity) = screen
+					{
+                    	cmd.entity(*entity).despawn();
+					}
+                    next.set(AppState::Gameplay);
+                });
+
+            ui.spawn(make_button(Buttons::Puzzle))
+                .with_children(|ui|
+                {
+                    ui.spawn(make_button_child(Buttons::Puzzle));
+                })
+                .observe(
+                |
+                     _: Trigger<Pointer<Click>>,
+                     mut next: ResMut<NextState<AppState>>,
+                     screen: Option<Single<Entity, (With<HomeScreen>, With<UiLayoutRoot>)>>,
+                     pack_handle: Res<PuzzlePackHandle>,
+                     packs: Res<Assets<PuzzlePackAsset>>,
+                     stats: Res<Stats>,
+                     mut message: Single<&mut Text2d, With<HomeMessage>>,
+                     mut cmd: Commands,
+                |
+                {
+                    let Some(pack) = packs.get(&pack_handle.0).map(|asset| &asset.pack) else
+                    {
+                        message.0 = "puzzle pack not loaded".to_owned();
+                        return;
+                    };
+
+                    let Some(index) = pack.next_level(|puzzle| stats.puzzles.contains(&puzzle.name)) else
+                    {
+                        message.0 = "puzzle pack is empty".to_owned();
+                        return;
+                    };
+
+                    let puzzle = &pack.puzzles[index];
+                    let grid = match puzzle.grid()
+                    {
+                        Ok(grid) => grid,
+                        Err(err) =>
+                        {
+                            message.0 = format!("failed to load puzzle [{}] with error [{}]", puzzle.name, err);
+                            return;
+                        }
+                    };
+
+                    cmd.insert_resource(HudMessage(format!("puzzle {} [{}], flag every mine then press enter", index + 1, puzzle.name)));
+                    cmd.insert_resource(ActivePuzzle{ name: puzzle.name.clone(), grid });
+                    if let Some(entity) = screen
+					{
+                    	cmd.entity(*entity).despawn();
+					}
+                    next.set(AppState::Gameplay);
+                });
+
             ui.spawn(make_button(Buttons::Custom))
                 .with_children(|ui|
                 {
@@ -120,6 +238,58 @@ pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>)
                     screens::custom::CustomScreen::spawn(cmd, a_serv);
                 });
 
+            ui.spawn(make_button(Buttons::Replay))
+                .with_children(|ui|
+                {
+                    ui.spawn(make_button_child(Buttons::Replay));
+                })
+                .observe(
+                |
+                     _: Trigger<Pointer<Click>>,
+                     mut next: ResMut<NextState<AppState>>,
+                     screen: Option<Single<Entity, (With<HomeScreen>, With<UiLayoutRoot>)>>,
+                     mut config: ResMut<GameConfig>,
+                     leaderboard: Res<Leaderboard>,
+                     mut cmd: Commands,
+                |
+                {
+                    let Some(best) = leaderboard.best(&config.board()) else
+                    {
+                        println!("no record for this board");
+                        return;
+                    };
+
+                    println!("replay {} moves", best.moves);
+                    config.set_board(&best.replay.board);
+                    cmd.insert_resource(ReplayPlayback::new(&best.replay.actions));
+                    if let Some(entity) = screen
+					{
+                    	cmd.entity(*entity).despawn();
+					}
+                    next.set(AppState::Gameplay);
+                });
+
+            ui.spawn(make_button(Buttons::Editor))
+                .with_children(|ui|
+                {
+                    ui.spawn(make_button_child(Buttons::Editor));
+                })
+                .observe(
+                |
+                     _: Trigger<Pointer<Click>>,
+                     mut next: ResMut<NextState<AppState>>,
+                     screen: Option<Single<Entity, (With<HomeScreen>, With<UiLayoutRoot>)>>,
+                     mut cmd: Commands,
+                |
+                {
+                    println!("editor");
+                    if let Some(entity) = screen
+					{
+                    	cmd.entity(*entity).despawn();
+					}
+                    next.set(AppState::Editor);
+                });
+
             ui.spawn(make_button(Buttons::Settings))
                 .with_children(|ui|
                 {
@@ -128,9 +298,16 @@ pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>)
                 .observe(
                 |
                      _: Trigger<Pointer<Click>>,
+                     screen: Option<Single<Entity, (With<HomeScreen>, With<UiLayoutRoot>)>>,
+                     mut cmd: Commands,
                 |
                 {
                     println!("settings");
+                    if let Some(entity) = screen
+					{
+                    	cmd.entity(*entity).despawn();
+					}
+                    cmd.init_resource::<SettingsScreenState>();
                 });
 
             ui.spawn(make_button(Buttons::Credits))
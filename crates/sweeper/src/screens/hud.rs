@@ -7,6 +7,13 @@ use crate::app_state::AppState;
 #[derive(Component)]
 pub struct HudScreen;
 
+// a line shown over the board for the whole game, e.g. puzzle instructions
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HudMessage(pub String);
+
+#[derive(Component)]
+pub struct HudMessageText;
+
 pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>)
 {
     commands.spawn((
@@ -40,5 +47,35 @@ pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>)
 			}
             next.set(AppState::Frontend);
         });
+
+        ui.spawn
+        ((
+            Name::new("Message"),
+            UiLayout::window().pos(Rl((50.0, 2.0))).anchor(Anchor::TopCenter).size(Rl((90.0, 6.0))).pack(),
+            layers::UI_RENDER_LAYER,
+            HudScreen,
+            Pickable::IGNORE,
+        ))
+        .with_children(|ui|
+        {
+            ui.spawn
+            ((
+                Name::new("Message Text"),
+                UiColor::from(Color::srgb(0.1, 0.1, 0.1)),
+                Text2d::default(),
+                layers::UI_RENDER_LAYER,
+                HudScreen,
+                HudMessageText,
+                Pickable::IGNORE,
+            ));
+        });
     });
 }
+
+pub fn update_message(message: Res<HudMessage>, mut texts: Query<&mut Text2d, With<HudMessageText>>)
+{
+    for mut text in &mut texts
+    {
+        text.0.clone_from(&message.0);
+    }
+}
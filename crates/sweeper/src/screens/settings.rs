@@ -0,0 +1,169 @@
+use crate::screens;
+
+use base::tuning::Tuning;
+use sup::actions::InputAction;
+use sup::actions::InputBinding;
+use sup::actions::InputMap;
+use vis::theme::Themes;
+
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContext;
+use bevy_egui::EguiContextPass;
+use bevy_egui::egui;
+use strum::IntoEnumIterator;
+
+pub struct SettingsScreen;
+
+// present while the settings screen is open, the home screen is respawned on close
+#[derive(Resource, Debug, Default)]
+pub struct SettingsScreenState
+{
+    // the action waiting for the next key, button or wheel
+    listening: Option<InputAction>,
+}
+
+impl SettingsScreen
+{
+    fn ui
+    (
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+        mut settings: ResMut<SettingsScreenState>,
+        mut map: ResMut<InputMap>,
+        mut themes: ResMut<Themes>,
+    )
+    {
+        let Ok(mut ctx) = egui_ctx.single_mut() else
+        {
+            return;
+        };
+
+        let mut close = false;
+        egui::Window::new("Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx.get_mut(), |ui|
+            {
+                // applied straight away, a board in play picks it up too
+                ui.horizontal(|ui|
+                {
+                    ui.label("Theme");
+                    let name = |path: &std::path::Path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+                    egui::ComboBox::from_id_salt("theme")
+                        .selected_text(name(&themes.current_path))
+                        .show_ui(ui, |ui|
+                        {
+                            for path in themes.available.clone()
+                            {
+                                if ui.selectable_label(path == themes.current_path, name(&path)).clicked()
+                                {
+                                    themes.select(&path, &asset_server);
+                                }
+                            }
+                        });
+                });
+
+                ui.separator();
+                egui::Grid::new("bindings").striped(true).show(ui, |ui|
+                {
+                    for action in InputAction::iter()
+                    {
+                        ui.label(action.to_string());
+                        ui.horizontal(|ui|
+                        {
+                            for binding in map.bindings(action).to_vec()
+                            {
+                                if ui.button(binding.to_string()).on_hover_text("remove").clicked()
+                                {
+                                    map.unbind(action, binding);
+                                }
+                            }
+
+                            if settings.listening == Some(action)
+                            {
+                                ui.label("press a key, escape to cancel");
+                            }
+                            else if ui.button("+").clicked()
+                            {
+                                settings.listening = Some(action);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui|
+                {
+                    if ui.button("Defaults").clicked()
+                    {
+                        *map = InputMap::default();
+                    }
+                    if ui.button("Save").clicked()
+                    {
+                        map.save();
+                        themes.selection().save();
+                    }
+                    if ui.button("Back").clicked()
+                    {
+                        close = true;
+                    }
+                });
+            });
+
+        if close
+        {
+            // unsaved changes only last until the game is closed
+            commands.remove_resource::<SettingsScreenState>();
+            screens::home::spawn(commands, asset_server);
+        }
+    }
+
+    fn capture
+    (
+        keys: Res<ButtonInput<KeyCode>>,
+        mouse: Res<ButtonInput<MouseButton>>,
+        gamepads: Query<&Gamepad>,
+        mut scroll_events: EventReader<MouseWheel>,
+        mut settings: ResMut<SettingsScreenState>,
+        mut map: ResMut<InputMap>,
+    )
+    {
+        let scrolled = scroll_events.read().count() > 0;
+        let Some(action) = settings.listening else
+        {
+            return;
+        };
+
+        if keys.just_pressed(KeyCode::Escape)
+        {
+            settings.listening = None;
+            return;
+        }
+
+        let binding = keys.get_just_pressed().next().map(|key| InputBinding::Key(*key))
+            .or_else(|| mouse.get_just_pressed().next().map(|button| InputBinding::Mouse(*button)))
+            .or_else(|| gamepads.iter().find_map(|gamepad| gamepad.get_just_pressed().next().map(|button| InputBinding::Gamepad(*button))))
+            .or(scrolled.then_some(InputBinding::MouseWheel));
+
+        if let Some(binding) = binding
+        {
+            map.bind(action, binding);
+            settings.listening = None;
+        }
+    }
+}
+
+impl Plugin for SettingsScreen
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .add_systems(EguiContextPass, SettingsScreen::ui.run_if(resource_exists::<SettingsScreenState>))
+            .add_systems(Update, SettingsScreen::capture.run_if(resource_exists::<SettingsScreenState>))
+            ;
+    }
+}
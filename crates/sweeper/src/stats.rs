@@ -0,0 +1,63 @@
+use base::save::SaveData;
+
+use bevy::prelude::*;
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DailyResult
+{
+    pub won: bool,
+    pub moves: u32,
+}
+
+impl DailyResult
+{
+    // a win always beats a loss, otherwise fewer moves is better
+    pub fn is_better_than(&self, other: &DailyResult) -> bool
+    {
+        (self.won, std::cmp::Reverse(self.moves)) > (other.won, std::cmp::Reverse(other.moves))
+    }
+}
+
+// local player stats, daily results are keyed by days since the unix epoch
+#[derive(Debug, Clone, Default, Resource, serde::Serialize, serde::Deserialize)]
+pub struct Stats
+{
+    pub daily: BTreeMap<u64, DailyResult>,
+    // names of solved puzzles
+    #[serde(default)]
+    pub puzzles: BTreeSet<String>,
+}
+
+impl SaveData for Stats
+{
+    fn path() -> &'static std::path::Path
+    {
+        std::path::Path::new("save/stats.ron")
+    }
+}
+
+impl Stats
+{
+    // returns true if this is the best result for the day so far
+    pub fn record_daily(&mut self, day: u64, result: DailyResult) -> bool
+    {
+        match self.daily.get(&day)
+        {
+            Some(best) if !result.is_better_than(best) => false,
+            _ =>
+            {
+                self.daily.insert(day, result);
+                true
+            }
+        }
+    }
+
+    // returns true the first time a puzzle is solved
+    pub fn record_puzzle(&mut self, name: &str) -> bool
+    {
+        self.puzzles.insert(name.to_owned())
+    }
+}
@@ -0,0 +1,271 @@
+use crate::input::CameraController;
+use crate::input::GameplayCamera;
+use crate::interactor::Interactor;
+
+use sim::replay::Action;
+use vis::board_vis_tuning::BoardVisTuning;
+use vis::grid_entities::GridVis;
+
+use bevy::input::touch::Touch;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use std::collections::HashMap;
+
+const LONG_PRESS_SECONDS: f32 = 0.5;
+// how far a finger can drift in logical pixels and still count as a tap or long press
+const TAP_SLOP: f32 = 12.0;
+
+// positions and deltas are in viewport coordinates
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum TouchGesture
+{
+    Tap(Vec2),
+    LongPress(Vec2),
+    Pan(Vec2),
+    Pinch(f32),
+}
+
+#[derive(Default)]
+struct TouchTracker
+{
+    pressed_at: HashMap<u64, f32>,
+    long_pressed: bool,
+    // set once a second finger lands, nothing fires on release until every finger is up
+    multi: bool,
+    last_pair: Option<(Vec2, f32)>,
+}
+
+fn is_still(touch: &Touch) -> bool
+{
+    touch.distance().length() < TAP_SLOP
+}
+
+fn detect_gestures
+(
+    touches: Res<Touches>,
+    time: Res<Time>,
+    mut tracker: Local<TouchTracker>,
+    mut gestures: EventWriter<TouchGesture>,
+)
+{
+    let now = time.elapsed_secs();
+    for touch in touches.iter_just_pressed()
+    {
+        tracker.pressed_at.insert(touch.id(), now);
+    }
+
+    let held: Vec<&Touch> = touches.iter().collect();
+    if let [a, b, ..] = held[..]
+    {
+        tracker.multi = true;
+        let centroid = (a.position() + b.position()) / 2.0;
+        let distance = a.position().distance(b.position());
+        if let Some((last_centroid, last_distance)) = tracker.last_pair
+        {
+            if centroid != last_centroid
+            {
+                gestures.write(TouchGesture::Pan(centroid - last_centroid));
+            }
+            if last_distance > 0.0 && distance != last_distance
+            {
+                gestures.write(TouchGesture::Pinch(distance / last_distance));
+            }
+        }
+        tracker.last_pair = Some((centroid, distance));
+    }
+    else
+    {
+        tracker.last_pair = None;
+        if let [touch] = held[..]
+            && !tracker.multi
+            && !tracker.long_pressed
+            && is_still(touch)
+            && tracker.pressed_at.get(&touch.id()).is_some_and(|pressed_at| now - pressed_at >= LONG_PRESS_SECONDS)
+        {
+            tracker.long_pressed = true;
+            gestures.write(TouchGesture::LongPress(touch.position()));
+        }
+    }
+
+    for touch in touches.iter_just_canceled()
+    {
+        tracker.pressed_at.remove(&touch.id());
+    }
+
+    for touch in touches.iter_just_released()
+    {
+        tracker.pressed_at.remove(&touch.id());
+        if !tracker.multi && !tracker.long_pressed && is_still(touch)
+        {
+            gestures.write(TouchGesture::Tap(touch.position()));
+        }
+    }
+
+    if held.is_empty()
+    {
+        tracker.multi = false;
+        tracker.long_pressed = false;
+    }
+}
+
+// tap reveals and long press flags, the same actions the mouse sends through the interactor
+pub fn touch_actions
+(
+    camera_query: Single<(&Camera, &GlobalTransform), (With<Camera2d>, With<GameplayCamera>)>,
+    mut gestures: EventReader<TouchGesture>,
+    board_vis_tuning: Res<BoardVisTuning>,
+    mut interactor: ResMut<Interactor>,
+    mut grid_vis: ResMut<GridVis>,
+)
+{
+    let (camera, camera_transform) = camera_query.into_inner();
+    for gesture in gestures.read()
+    {
+        let (position, action): (Vec2, fn(_) -> Action) = match *gesture
+        {
+            TouchGesture::Tap(position) => (position, Action::Primary),
+            TouchGesture::LongPress(position) => (position, Action::Secondary),
+            TouchGesture::Pan(_) | TouchGesture::Pinch(_) => continue,
+        };
+
+        let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, position) else
+        {
+            continue;
+        };
+
+        if let Some(pos) = Interactor::world_to_cell(&grid_vis.grid, &board_vis_tuning, &world_pos)
+        {
+            interactor.apply(&mut grid_vis.grid, action(pos));
+        }
+    }
+}
+
+pub fn touch_camera
+(
+    mut gestures: EventReader<TouchGesture>,
+    camera_query: Single<(&mut Transform, &mut Projection, &CameraController), (With<Camera2d>, With<GameplayCamera>)>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    grid_vis: Res<GridVis>,
+    board_vis_tuning: Res<BoardVisTuning>,
+)
+{
+    let board = CameraController::board_rect(&grid_vis, &board_vis_tuning);
+    let (mut camera_transform, mut projection_query, controller) = camera_query.into_inner();
+    let Projection::Orthographic(ref mut projection) = *projection_query else
+    {
+        return;
+    };
+
+    for gesture in gestures.read()
+    {
+        match *gesture
+        {
+            TouchGesture::Pan(delta) =>
+            {
+                camera_transform.translation.x -= delta.x * projection.scale;
+                camera_transform.translation.y += delta.y * projection.scale; // Y is inverted in screen space
+            }
+            TouchGesture::Pinch(ratio) =>
+            {
+                projection.scale = controller.clamp_scale(projection.scale / ratio, window.size(), board);
+            }
+            TouchGesture::Tap(_) | TouchGesture::LongPress(_) => {}
+        }
+    }
+}
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .add_event::<TouchGesture>()
+            .add_systems(PreUpdate, detect_gestures.after(bevy::input::InputSystem))
+            ;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use bevy::input::touch::TouchInput;
+    use bevy::input::touch::TouchPhase;
+    use bevy::time::TimeUpdateStrategy;
+
+    // events only live for two frames, so gather them as they come
+    #[derive(Resource, Default)]
+    struct Collected(Vec<TouchGesture>);
+
+    fn collect(mut gestures: EventReader<TouchGesture>, mut collected: ResMut<Collected>)
+    {
+        collected.0.extend(gestures.read().copied());
+    }
+
+    fn app() -> App
+    {
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, bevy::input::InputPlugin, TouchPlugin))
+            .init_resource::<Collected>()
+            .add_systems(Update, collect)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(std::time::Duration::from_millis(100)));
+        app.update();
+        app
+    }
+
+    fn touch(app: &mut App, id: u64, phase: TouchPhase, position: Vec2)
+    {
+        app.world_mut().send_event(TouchInput{ phase, position, window: Entity::PLACEHOLDER, force: None, id });
+        app.update();
+    }
+
+    fn gestures(app: &mut App) -> Vec<TouchGesture>
+    {
+        std::mem::take(&mut app.world_mut().resource_mut::<Collected>().0)
+    }
+
+    #[test]
+    fn test_tap()
+    {
+        let mut app = app();
+        touch(&mut app, 0, TouchPhase::Started, Vec2::new(10.0, 10.0));
+        touch(&mut app, 0, TouchPhase::Moved, Vec2::new(12.0, 10.0));
+        touch(&mut app, 0, TouchPhase::Ended, Vec2::new(12.0, 10.0));
+        assert_eq!(gestures(&mut app), vec![TouchGesture::Tap(Vec2::new(12.0, 10.0))]);
+
+        // dragging too far is not a tap
+        touch(&mut app, 1, TouchPhase::Started, Vec2::new(10.0, 10.0));
+        touch(&mut app, 1, TouchPhase::Moved, Vec2::new(60.0, 10.0));
+        touch(&mut app, 1, TouchPhase::Ended, Vec2::new(60.0, 10.0));
+        assert!(gestures(&mut app).is_empty());
+    }
+
+    #[test]
+    fn test_long_press()
+    {
+        let mut app = app();
+        touch(&mut app, 0, TouchPhase::Started, Vec2::new(10.0, 10.0));
+        for _ in 0..8
+        {
+            app.update();
+        }
+        touch(&mut app, 0, TouchPhase::Ended, Vec2::new(10.0, 10.0));
+        assert_eq!(gestures(&mut app), vec![TouchGesture::LongPress(Vec2::new(10.0, 10.0))]);
+    }
+
+    #[test]
+    fn test_pan_and_pinch()
+    {
+        let mut app = app();
+        touch(&mut app, 0, TouchPhase::Started, Vec2::new(0.0, 0.0));
+        touch(&mut app, 1, TouchPhase::Started, Vec2::new(10.0, 0.0));
+        touch(&mut app, 1, TouchPhase::Moved, Vec2::new(20.0, 0.0));
+        touch(&mut app, 0, TouchPhase::Ended, Vec2::new(0.0, 0.0));
+        touch(&mut app, 1, TouchPhase::Ended, Vec2::new(20.0, 0.0));
+        assert_eq!(gestures(&mut app), vec![TouchGesture::Pan(Vec2::new(5.0, 0.0)), TouchGesture::Pinch(2.0)]);
+    }
+}
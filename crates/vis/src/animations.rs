@@ -0,0 +1,148 @@
+use crate::board_vis_tuning::BoardVisTuning;
+use crate::grid_entities::CellChunks;
+use crate::grid_entities::GridVis;
+
+use base::point::Point;
+use sim::grid::CellState;
+
+use bevy::prelude::*;
+
+// one-off effects that aren't changes to the grid itself
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum BoardEffect
+{
+    Explosion(Point),
+    // show every mine one after another, nearest the last explosion first
+    RevealMines,
+}
+
+// cells whose texels are held back until their turn in a ripple or the loss sequence
+#[derive(Resource, Debug, Default)]
+pub struct CellAnimations
+{
+    // when to show the cell, sorted, with state bits shown on top of the grid's
+    pending: Vec<(f32, Point, CellState)>,
+    // start times
+    explosions: Vec<(f32, Point)>,
+    last_explosion: Option<Point>,
+}
+
+impl CellAnimations
+{
+    fn schedule(&mut self, cells: impl IntoIterator<Item = (f32, Point, CellState)>)
+    {
+        self.pending.extend(cells);
+        self.pending.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    pub(crate) fn ripple(&mut self, now: f32, cells: &[Point], depths: &[u32], step: f32)
+    {
+        self.schedule(cells.iter().zip(depths).map(|(pos, depth)| (now + *depth as f32 * step, *pos, CellState::None)));
+    }
+
+    pub(crate) fn clear(&mut self)
+    {
+        self.pending.clear();
+    }
+}
+
+pub fn apply_effects
+(
+    time: Res<Time>,
+    mut effects: EventReader<BoardEffect>,
+    mut animations: ResMut<CellAnimations>,
+    grid_vis: Res<GridVis>,
+    vis_tuning: Res<BoardVisTuning>,
+)
+{
+    let now = time.elapsed_secs();
+    let tuning = &vis_tuning.animation;
+
+    // explosions first so the mines spread out from them
+    let mut effects: Vec<BoardEffect> = effects.read().cloned().collect();
+    effects.sort_by_key(|effect| matches!(effect, BoardEffect::RevealMines));
+    for effect in effects
+    {
+        match effect
+        {
+            BoardEffect::Explosion(pos) =>
+            {
+                animations.explosions.push((now, pos));
+                animations.last_explosion = Some(pos);
+                animations.schedule([(now, pos, CellState::Revealed)]);
+            }
+            BoardEffect::RevealMines =>
+            {
+                let grid = &grid_vis.grid;
+                let origin = animations.last_explosion.unwrap_or_else(|| Point::new(grid.size().width / 2, grid.size().height / 2));
+                let mut mines: Vec<Point> = grid.states
+                    .index2_space()
+                    .filter(|pos| *pos != origin && grid.states[*pos].contains(CellState::Mine))
+                    .collect();
+                mines.sort_by_key(|pos| (**pos - *origin).length_squared());
+
+                let interval = tuning.mine_reveal_interval.min(tuning.mine_reveal_max / mines.len().max(1) as f32);
+                animations.schedule(mines.into_iter().enumerate().map(|(index, pos)|
+                {
+                    (now + (index + 1) as f32 * interval, pos, CellState::Revealed)
+                }));
+            }
+        }
+    }
+}
+
+pub fn animate_cells
+(
+    time: Res<Time>,
+    mut animations: ResMut<CellAnimations>,
+    chunks: Res<CellChunks>,
+    mut images: ResMut<Assets<Image>>,
+    grid_vis: Res<GridVis>,
+)
+{
+    let now = time.elapsed_secs();
+    let due = animations.pending.partition_point(|(at, _, _)| *at <= now);
+    if due == 0
+    {
+        return;
+    }
+
+    let cells = animations.pending.drain(..due).map(|(_, pos, shown)| (pos, shown));
+    chunks.show(&mut images, &grid_vis.grid, cells);
+}
+
+pub fn draw_explosions
+(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    mut animations: ResMut<CellAnimations>,
+    vis_tuning: Res<BoardVisTuning>,
+)
+{
+    const FLASH: Color = Color::srgb(1.0, 0.85, 0.4);
+    const SHOCKWAVE: Color = Color::srgb(1.0, 0.4, 0.1);
+
+    let now = time.elapsed_secs();
+    let tuning = &vis_tuning.animation;
+    animations.explosions.retain(|(start, _)| now - start < tuning.explosion_duration);
+
+    let cell_size = vis_tuning.cell_size;
+    for (start, pos) in &animations.explosions
+    {
+        let t = (now - start) / tuning.explosion_duration;
+        let center = (pos.as_vec2() + 0.5) * cell_size;
+        let radius = t * tuning.explosion_radius * cell_size.max_element();
+        let fade = 1.0 - t;
+
+        // a bright flash over the mine that shrinks as the shockwave leaves it
+        gizmos.circle_2d(center, cell_size.max_element() * 0.5 * fade, FLASH.with_alpha(fade));
+        for ring in 0..3
+        {
+            let ring_radius = radius - ring as f32 * 2.0;
+            if ring_radius > 0.0
+            {
+                gizmos.circle_2d(center, ring_radius, SHOCKWAVE.with_alpha(fade / (ring + 1) as f32));
+            }
+        }
+    }
+}
@@ -1,13 +1,16 @@
 
 use base::tuning::Tuning;
 use bevy::prelude::*;
-use bevy::reflect::TypePath;
 
-#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, serde::Deserialize, serde::Serialize, Reflect)]
 pub struct GridTuning
 {
     pub world_line_width: f32, // world space
     pub uv_width: f32, // uv/normalized (0.0, 1.0)
+    pub line_color: Srgba,
+    // edges between the board and the land
+    pub coast_line_width: f32, // world space
+    pub coast_color: Srgba,
 }
 
 impl Default for GridTuning
@@ -16,18 +19,50 @@ impl Default for GridTuning
     {
         Self
         {
-            world_line_width: 1.0,
+            world_line_width: 4.0,
             uv_width: 0.5,
+            line_color: Srgba::BLACK,
+            coast_line_width: 6.0,
+            coast_color: Srgba::rgb(0.93, 0.87, 0.7),
         }
     }
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Asset, Resource, TypePath)]
+// seconds unless noted
+#[derive(Clone, serde::Deserialize, serde::Serialize, Reflect)]
+pub struct AnimationTuning
+{
+    // delay per cell of distance from the click before a cell pops open
+    pub reveal_ripple: f32,
+    pub explosion_duration: f32,
+    pub explosion_radius: f32, // cells
+    // delay between mines shown after a loss, shortened so the whole sequence fits in mine_reveal_max
+    pub mine_reveal_interval: f32,
+    pub mine_reveal_max: f32,
+}
+
+impl Default for AnimationTuning
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            reveal_ripple: 0.025,
+            explosion_duration: 0.6,
+            explosion_radius: 4.0,
+            mine_reveal_interval: 0.08,
+            mine_reveal_max: 3.0,
+        }
+    }
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize, Asset, Resource, Reflect)]
 pub struct BoardVisTuning 
 {
     pub cell_size: Vec2,
     pub grid: GridTuning,
-    pub adjacency_images: [std::path::PathBuf; 8],
+    #[serde(default)]
+    pub animation: AnimationTuning,
 }
 
 impl Tuning for BoardVisTuning
@@ -46,16 +81,57 @@ impl Default for BoardVisTuning
         {
             cell_size: Vec2::splat(28.0),
             grid: Default::default(),
-            adjacency_images: [
-                "adjacency/1.png".into(),
-                "adjacency/2.png".into(),
-                "adjacency/3.png".into(),
-                "adjacency/4.png".into(),
-                "adjacency/5.png".into(),
-                "adjacency/6.png".into(),
-                "adjacency/7.png".into(),
-                "adjacency/8.png".into(),
-            ],
+            animation: Default::default(),
+        }
+    }
+}
+
+// the sea around and between the islands, elevation is 0 in open water and 1 on land
+#[derive(Clone, serde::Deserialize, serde::Serialize, Asset, Resource, Reflect)]
+pub struct TerrainTuning
+{
+    // elevation where water turns to land
+    pub shore_level: f32,
+    pub shallow_color: Srgba,
+    pub deep_color: Srgba,
+    pub foam_color: Srgba,
+    // wave cells per board cell
+    pub wave_scale: f32,
+    // waves per second, the second layer scrolls against it
+    pub wave_speed: Vec2,
+    // how much the waves bend the light
+    pub wave_strength: f32,
+    // elevation below the shore that still gets foam
+    pub foam_width: f32,
+    pub foam_speed: f32,
+    // foam only forms where elevation changes quickly, higher is more foam
+    pub foam_sharpness: f32,
+}
+
+impl Tuning for TerrainTuning
+{
+    fn path() -> &'static std::path::Path
+    {
+        std::path::Path::new("tuning/terrain.ron")
+    }
+}
+
+impl Default for TerrainTuning
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            shore_level: 0.5,
+            shallow_color: Srgba::rgb(0.2, 0.62, 0.68),
+            deep_color: Srgba::rgb(0.05, 0.2, 0.38),
+            foam_color: Srgba::new(0.95, 0.97, 1.0, 0.85),
+            wave_scale: 0.35,
+            wave_speed: Vec2::new(0.12, 0.05),
+            wave_strength: 1.5,
+            foam_width: 0.2,
+            foam_speed: 2.0,
+            foam_sharpness: 12.0,
         }
     }
 }
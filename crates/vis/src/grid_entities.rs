@@ -1,330 +1,749 @@
-
+use crate::animations::BoardEffect;
+use crate::animations::CellAnimations;
 use crate::board_vis_tuning::*;
 use crate::layers;
+use crate::theme::BoardTheme;
+use crate::theme::Themes;
 
+use base::debug_name;
 use base::point::Point;
 use base::array2::Array2;
 use base::extents::Extents;
 use sim::grid::*;
+use sim::logic::ActionResult;
+use sim::logic::LogicPreview;
+use sim::logic::PreviewKind;
+use sim::logic::PreviewResult;
+
+use bevyx::atlas::Atlas;
 
 use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::mesh::MeshVertexBufferLayoutRef;
+use bevy::render::render_asset::*;
+use bevy::render::render_resource::*;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::render::render_resource::ShaderRef;
 use bevy::sprite::*;
 
-use std::marker::PhantomData;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Resource)]
-pub struct EntityGrid<Tag>
+// cells along each side of a chunk, only chunks with changed cells are uploaded again
+pub(crate) const CHUNK_SIZE: i32 = 128;
+
+// channels of a cell in the chunk data texture
+const STATE: usize = 0;
+const ADJACENCY: usize = 1;
+const TINT: usize = 2;
+
+// sprites in the atlas, numbers follow the mine
+const SPRITE_COUNT: usize = 11;
+
+// tint channel values
+const UNTINTED: u8 = 0;
+const HOVERED: u8 = 1;
+const PRESSED: u8 = 2;
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct CellMaterial
 {
-    known: Array2<Option<Entity>>,
-    _tag: PhantomData<Tag>,
+    #[uniform(0)]
+    tint: LinearRgba,
+
+    // one texel per cell, read with textureLoad
+    #[texture(1, sample_type="float", filterable=false)]
+    cells: Handle<Image>,
+
+    // every sprite, packed by the assets tool
+    #[texture(2)]
+    #[sampler(3)]
+    sprites: Handle<Image>,
+    // uv min and max of each sprite in the atlas, in sprite order
+    #[uniform(4)]
+    sprite_rects: [Vec4; SPRITE_COUNT],
+    #[uniform(5)]
+    number_colors: [LinearRgba; 8],
 }
 
-impl<Tag> EntityGrid<Tag>
+impl CellMaterial
 {
-    fn new(size: Extents) -> Self
+    fn new(cells: Handle<Image>, handles: &VisHandles) -> Self
     {
-        Self
+        let mut material = Self
         {
-            known: Array2::from_size(size),
-            _tag: PhantomData::<Tag>,
-        }
+            tint: Color::WHITE.into(),
+            cells,
+            sprites: default(),
+            sprite_rects: default(),
+            number_colors: default(),
+        };
+        material.apply_handles(handles);
+        material
+    }
+
+    pub(crate) fn apply_handles(&mut self, handles: &VisHandles)
+    {
+        self.sprites = handles.sprites.clone();
+        self.sprite_rects = handles.sprite_rects;
+        self.number_colors = handles.number_colors;
     }
 }
 
-pub trait VisTag
+impl Material2d for CellMaterial
 {
-    fn include_state() -> CellState;
-    fn exclude_state() -> CellState;
-    
-    fn should_show(state: &CellState) -> bool
+    fn fragment_shader() -> ShaderRef
     {
-        let include_state = Self::include_state();
-        let exclude_state = Self::exclude_state();
-        let include = ( *state & include_state ) == include_state;
-        let exclude = ( *state & exclude_state ) == CellState::None;
-        include && exclude
+        "shaders/cells.wgsl".into()
     }
 
-    // TODO just spawn the whole bloody entity?
-    fn layer() -> f32;
-    fn sprite(vis_tuning: &BoardVisTuning, handles: &VisHandles) -> Sprite;
-}
+    fn alpha_mode(&self) -> AlphaMode2d
+    {
+        AlphaMode2d::Blend
+    }
 
-// TODO schecko
-#[allow(dead_code)]
-#[derive(Debug, Clone, Component)]
-pub struct EntityIndex2(Point);
+    fn specialize(
+        _descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError>
+    {
+        Ok(())
+    }
+}
 
-// TODO schecko
-#[allow(dead_code)]
 #[derive(Debug, Clone, Component)]
-pub struct EntityIndex(usize);
+pub struct CellChunk;
 
-#[derive(Debug, Clone, Component, Default)]
-pub struct Mine;
+#[derive(Debug, Clone, Resource)]
+pub struct CellChunks
+{
+    // the data texture of every chunk, indexed by chunk position
+    images: Array2<Handle<Image>>,
+}
 
-impl VisTag for Mine
+impl CellChunks
 {
-    fn include_state() -> CellState { CellState::Mine | CellState::Revealed }
-    fn exclude_state() -> CellState { CellState::None }
-    fn layer() -> f32 { layers::MINE }
+    fn chunk_of(pos: Point) -> (Point, Point)
+    {
+        let chunk = Point::new(pos.x / CHUNK_SIZE, pos.y / CHUNK_SIZE);
+        let local = Point::new(pos.x % CHUNK_SIZE, pos.y % CHUNK_SIZE);
+        (chunk, local)
+    }
 
-    fn sprite(vis_tuning: &BoardVisTuning, handles: &VisHandles) -> Sprite
+    // rewrites the given cells, each chunk with a changed texel is marked for upload once
+    fn update<T>
+    (
+        &self,
+        images: &mut Assets<Image>,
+        cells: impl IntoIterator<Item = (Point, T)>,
+        update: impl Fn(&mut [u8], Point, T),
+    )
     {
-        Sprite
+        let mut changed = HashMap::<usize, Vec<(usize, [u8; 4])>>::new();
+        for (pos, value) in cells
         {
-            image: handles.mine.clone(),
-            custom_size: Some(vis_tuning.cell_size),
-            anchor: Anchor::BottomLeft,
-            ..default()
+            let (chunk, local) = Self::chunk_of(pos);
+            let Some(index) = self.images.get_index(chunk) else
+            {
+                continue;
+            };
+            let Some(image) = images.get(&self.images[index]) else
+            {
+                continue;
+            };
+            let Some(data) = &image.data else
+            {
+                continue;
+            };
+            if local.x >= image.width() as i32 || local.y >= image.height() as i32
+            {
+                continue;
+            }
+
+            let offset = texel_offset(image.width() as i32, local);
+            let mut texel: [u8; 4] = data[offset..offset + 4].try_into().unwrap();
+            update(&mut texel, pos, value);
+            if data[offset..offset + 4] != texel
+            {
+                changed.entry(index).or_default().push((offset, texel));
+            }
         }
+
+        for (index, texels) in changed
+        {
+            if let Some(data) = images.get_mut(&self.images[index]).and_then(|image| image.data.as_mut())
+            {
+                for (offset, texel) in texels
+                {
+                    data[offset..offset + 4].copy_from_slice(&texel);
+                }
+            }
+        }
+    }
+
+    // writes cells from the grid with extra state bits on top, animations use this to show mines
+    pub(crate) fn show(&self, images: &mut Assets<Image>, grid: &Grid, cells: impl IntoIterator<Item = (Point, CellState)>)
+    {
+        self.update(images, cells, |texel, pos, shown|
+        {
+            write_cell(texel, grid, pos);
+            texel[STATE] |= shown.bits();
+        });
     }
 }
 
-#[derive(Debug, Clone, Component, Default)]
-pub struct Cover;
+fn texel_offset(width: i32, local: Point) -> usize
+{
+    (local.y * width + local.x) as usize * 4
+}
 
-impl VisTag for Cover
+pub(crate) fn chunk_cells(board: Extents, chunk: Point) -> Extents
 {
-    fn include_state() -> CellState { CellState::None }
-    fn exclude_state() -> CellState { CellState::NonPlayable | CellState::Revealed }
-    fn layer() -> f32 { layers::COVER }
+    Extents::new
+    (
+        CHUNK_SIZE.min(board.width - chunk.x * CHUNK_SIZE),
+        CHUNK_SIZE.min(board.height - chunk.y * CHUNK_SIZE),
+    )
+}
 
-    fn sprite(vis_tuning: &BoardVisTuning, handles: &VisHandles) -> Sprite
-    {
-        Sprite
-        {
-            image: handles.cover.clone(),
-            custom_size: Some(vis_tuning.cell_size),
-            anchor: Anchor::BottomLeft,
-            ..default()
-        }
-    }
+fn write_cell(texel: &mut [u8], grid: &Grid, pos: Point)
+{
+    texel[STATE] = grid.states[pos].bits();
+    texel[ADJACENCY] = grid.adjacency[pos];
+}
+
+fn chunk_mesh(size: Vec2) -> Mesh
+{
+    let v_pos = vec!
+    [
+        [0.0, 0.0, 0.0], // BL
+        [size.x, 0.0, 0.0], // BR
+        [0.0, size.y, 0.0], // TL
+        [size.x, size.y, 0.0], // TR
+    ];
+    let v_uv: Vec<[f32; 2]> = vec!
+    [
+        [0.0, 0.0],
+        [1.0, 0.0],
+        [0.0, 1.0],
+        [1.0, 1.0],
+    ];
+
+    let indices = vec!
+    [
+        0, 2, 1,
+        1, 2, 3
+    ];
+
+    let mut mesh = Mesh::new
+    (
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, v_pos);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, v_uv);
+    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
+    mesh
 }
 
-#[derive(Debug, Clone, Component, Default)]
-pub struct Flag;
+#[derive(Debug, Clone, Resource)]
+pub struct GridVis
+{
+    // TODO I dont want vis to own the sim, it should be in svc or above
+    pub grid: Grid,
+}
 
-impl VisTag for Flag
+// sent whenever GridVis changes, views redo only the cells listed
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum GridChanged
 {
-    fn include_state() -> CellState { CellState::Flag }
-    fn exclude_state() -> CellState { CellState::None }
-    fn layer() -> f32 { layers::FLAG }
+    Cells(Vec<Point>),
+    // opened by a guess or chord, shown as a ripple that reaches each cell after its reveal depth
+    Revealed{ cells: Vec<Point>, depths: Vec<u32> },
+    // the grid was replaced or edited as a whole
+    All,
+}
 
-    fn sprite(vis_tuning: &BoardVisTuning, handles: &VisHandles) -> Sprite
+impl GridChanged
+{
+    pub fn from_result(result: &ActionResult) -> Vec<GridChanged>
     {
-        Sprite
+        match result
         {
-            image: handles.flag.clone(),
-            custom_size: Some(vis_tuning.cell_size),
-            anchor: Anchor::BottomLeft,
-            ..default()
+            // mines moved out of the way are redrawn first, the opening still ripples
+            ActionResult::FirstGuess(first) => vec!
+            [
+                GridChanged::Cells(first.changed.clone()),
+                GridChanged::Revealed{ cells: first.guess.revealed.clone(), depths: first.guess.depths.clone() },
+            ],
+            ActionResult::Guess(guess) | ActionResult::Chord(guess) => vec![GridChanged::Revealed{ cells: guess.revealed.clone(), depths: guess.depths.clone() }],
+            ActionResult::Flag(flag) => vec![GridChanged::Cells(vec![flag.pos])],
         }
     }
 }
 
-#[derive(Debug, Clone, Component, Default)]
-pub struct Adjacency;
-
-impl VisTag for Adjacency
+// the action under the pointer, exposed so covers can be drawn hovered or pushed in
+#[derive(Debug, Clone, Default, Resource)]
+pub struct CellPreview
 {
-    fn include_state() -> CellState { CellState::Revealed }
-    fn exclude_state() -> CellState { CellState::NonPlayable | CellState::Mine }
-    fn layer() -> f32 { layers::ADJACENCY }
+    pub preview: Option<LogicPreview>,
+    pub pressed: bool,
+}
 
-    fn sprite(_vis_tuning: &BoardVisTuning, _handles: &VisHandles) -> Sprite
+impl CellPreview
+{
+    // a held chord pushes in its whole covered neighbourhood, even if it won't go off
+    pub fn pressed_cells(preview: &LogicPreview, grid: &Grid) -> Vec<Point>
     {
-        panic!("Manually handled, maybe split into another trait?");
+        match preview.kind
+        {
+            PreviewKind::FirstGuess | PreviewKind::Guess if preview.result != PreviewResult::Nothing => vec![preview.pos],
+            PreviewKind::FirstGuess | PreviewKind::Guess | PreviewKind::Flag => Vec::new(),
+            PreviewKind::Chord => grid
+                .neighbours(preview.pos)
+                .filter(|pos| !grid.states[*pos].intersects(CellState::Revealed | CellState::Flag | CellState::NonPlayable))
+                .collect(),
+        }
     }
 }
 
+// the current theme's images, loaded
 #[derive(Debug, Clone, Resource)]
-pub struct GridVis
+pub struct VisHandles
 {
-    // TODO I dont want vis to own the sim, it should be in svc or above
-    pub grid: Grid,
+    atlas: Handle<Atlas>,
+    // names in the atlas, in sprite order
+    sprite_names: [String; SPRITE_COUNT],
+    // taken from the atlas once it has loaded
+    sprites: Handle<Image>,
+    sprite_rects: [Vec4; SPRITE_COUNT],
+    pub(crate) grid_line: Handle<Image>,
+    pub(crate) terrain_palette: Handle<Image>,
+    number_colors: [LinearRgba; 8],
 }
 
-#[derive(Debug, Clone, Resource)]
-pub struct VisHandles
+impl VisHandles
 {
-    mine: Handle<Image>,
-    flag: Handle<Image>,
-    cover: Handle<Image>,
-    adjacency: [Handle<Image>; 8],
+    pub fn new(theme: &BoardTheme, asset_server: &AssetServer) -> Self
+    {
+        let [one, two, three, four, five, six, seven, eight] = theme.numbers.clone();
+        Self
+        {
+            atlas: asset_server.load(theme.atlas.clone()),
+            sprite_names: [theme.cover.clone(), theme.flag.clone(), theme.mine.clone(), one, two, three, four, five, six, seven, eight],
+            sprites: default(),
+            sprite_rects: default(),
+            grid_line: asset_server.load(theme.grid_line.clone()),
+            terrain_palette: asset_server.load(theme.terrain_palette.clone()),
+            number_colors: theme.number_colors.map(LinearRgba::from),
+        }
+    }
+
+    // false until the atlas has loaded
+    pub fn resolve(&mut self, atlases: &Assets<Atlas>) -> bool
+    {
+        let Some(atlas) = atlases.get(&self.atlas) else
+        {
+            return false;
+        };
+
+        self.sprites = atlas.image.clone();
+        // half a texel in so filtering stays inside the sprite
+        let inset = atlas.texel_size() * 0.5;
+        for (rect, name) in self.sprite_rects.iter_mut().zip(&self.sprite_names)
+        {
+            *rect = match atlas.uv_rect(name)
+            {
+                Some(uv) => Vec4::new(uv.min.x + inset.x, uv.min.y + inset.y, uv.max.x - inset.x, uv.max.y - inset.y),
+                None =>
+                {
+                    eprintln!("{} -- No sprite [{}] in the atlas", debug_name!(), name);
+                    Vec4::ZERO
+                }
+            };
+        }
+        true
+    }
+
+    pub fn is_atlas(&self, id: AssetId<Atlas>) -> bool
+    {
+        self.atlas.id() == id
+    }
+
+    pub fn contains(&self, id: AssetId<Image>) -> bool
+    {
+        [&self.sprites, &self.grid_line, &self.terrain_palette]
+            .into_iter()
+            .any(|handle| handle.id() == id)
+    }
 }
 
+// the default theme stands in until the selected one has loaded
 pub fn init_handles
 (
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    vis_tuning: Res<BoardVisTuning>,
+    themes: Res<Themes>,
+    theme_assets: Res<Assets<BoardTheme>>,
+    atlases: Res<Assets<Atlas>>,
 )
 {
-    let mut adj_it = vis_tuning.adjacency_images
-        .iter()
-        .map( |path| asset_server.load(&**path) );
-    let adjacency = std::array::from_fn::<Handle<Image>, 8, _>( |_| adj_it.next().unwrap() );
-
-    commands.insert_resource
-    (
-        VisHandles
-        {
-            mine: asset_server.load("textures/mine.png"),
-            flag: asset_server.load("textures/flag.png"),
-            cover: asset_server.load("textures/cover.png"),
-            adjacency,
-        }
-    );
+    let mut handles = match theme_assets.get(&themes.current)
+    {
+        Some(theme) => VisHandles::new(theme, &asset_server),
+        None => VisHandles::new(&BoardTheme::default(), &asset_server),
+    };
+    handles.resolve(&atlases);
+    commands.insert_resource(handles);
 }
 
-pub fn init_known
+pub fn spawn_chunks
 (
     mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<CellMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    vis_tuning: Res<BoardVisTuning>,
     grid_vis: Res<GridVis>,
+    handles: Res<VisHandles>,
 )
 {
-    let size = grid_vis.grid.states.size();
-    commands.insert_resource
-    (
-        EntityGrid::<Mine>::new(size),
-    );
-    commands.insert_resource
-    (
-        EntityGrid::<Flag>::new(size),
-    );
-    commands.insert_resource
-    (
-        EntityGrid::<Cover>::new(size),
-    );
-    commands.insert_resource
+    let grid = &grid_vis.grid;
+    let board = grid.size();
+    let chunk_extents = Extents::new
     (
-        EntityGrid::<Adjacency>::new(size),
+        (board.width + CHUNK_SIZE - 1) / CHUNK_SIZE,
+        (board.height + CHUNK_SIZE - 1) / CHUNK_SIZE,
     );
+
+    // only edge chunks are smaller, so there are at most four meshes
+    let mut chunk_meshes = HashMap::<(i32, i32), Handle<Mesh>>::new();
+    let mut chunk_images = Vec::with_capacity(chunk_extents.num_elements());
+    for chunk in chunk_extents.index2_space()
+    {
+        let cells = chunk_cells(board, chunk);
+        let origin = Point::new(chunk.x * CHUNK_SIZE, chunk.y * CHUNK_SIZE);
+
+        let mut data = vec![0; cells.num_elements() * 4];
+        for local in cells.index2_space()
+        {
+            let offset = texel_offset(cells.width, local);
+            write_cell(&mut data[offset..offset + 4], grid, Point::new(origin.x + local.x, origin.y + local.y));
+        }
+        let image = Image::new
+        (
+            Extent3d{
+                width: cells.width as u32,
+                height: cells.height as u32,
+                depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD
+        );
+        let image = images.add(image);
+
+        let material = materials.add(CellMaterial::new(image.clone(), &handles));
+
+        let mesh = chunk_meshes
+            .entry((cells.width, cells.height))
+            .or_insert_with(|| meshes.add(chunk_mesh(cells.as_vec2() * vis_tuning.cell_size)))
+            .clone();
+
+        let world_pos = origin.as_vec2() * vis_tuning.cell_size;
+        commands.spawn
+        ((
+            CellChunk,
+            Mesh2d(mesh),
+            MeshMaterial2d(material),
+            Transform::from_translation(world_pos.extend(layers::CELLS)),
+        ));
+        chunk_images.push(image);
+    }
+
+    commands.insert_resource(CellChunks
+    {
+        images: Array2::from_values(&chunk_images, chunk_extents).unwrap(),
+    });
+    commands.insert_resource(CellAnimations::default());
 }
 
-pub fn sync_grid_entities<Tag>
+pub fn sync_chunks
 (
-    mut commands: Commands,
-    vis_tuning: Res<BoardVisTuning>,
+    time: Res<Time>,
+    mut changes: EventReader<GridChanged>,
+    chunks: Res<CellChunks>,
+    mut animations: ResMut<CellAnimations>,
+    mut images: ResMut<Assets<Image>>,
     grid_vis: Res<GridVis>,
-    mut entity_grid: ResMut<EntityGrid<Tag>>,
-    handles: Res<VisHandles>,
+    vis_tuning: Res<BoardVisTuning>,
 )
-    where Tag: Component + Default + VisTag
 {
-    let size = grid_vis.grid.states.size();
-    for index2 in size.index2_space()
+    let grid = &grid_vis.grid;
+    for change in changes.read()
     {
-        let state = grid_vis.grid.states.get_by_index2(index2).unwrap();
-        let vis = &mut entity_grid.known.get_by_index2_mut(index2).unwrap();
+        match change
+        {
+            GridChanged::Cells(cells) =>
+            {
+                chunks.update(&mut images, cells.iter().map(|pos| (*pos, ())), |texel, pos, ()| write_cell(texel, grid, pos));
+            }
+            GridChanged::Revealed{ cells, depths } =>
+            {
+                // held covered until the ripple gets there
+                chunks.update(&mut images, cells.iter().map(|pos| (*pos, ())), |texel, pos, ()|
+                {
+                    write_cell(texel, grid, pos);
+                    texel[STATE] &= !CellState::Revealed.bits();
+                });
+                animations.ripple(time.elapsed_secs(), cells, depths, vis_tuning.animation.reveal_ripple);
+            }
+            GridChanged::All =>
+            {
+                animations.clear();
+                sync_all(&chunks, &mut images, grid);
+            }
+        }
+    }
+}
 
-        if Tag::should_show(state) == vis.is_some()
+// compares every cell against its chunk, but only chunks that differ are written and uploaded
+fn sync_all(chunks: &CellChunks, images: &mut Assets<Image>, grid: &Grid)
+{
+    let board = grid.size();
+    for (index, handle) in chunks.images.iter().enumerate()
+    {
+        let chunk = chunks.images.get_index2(index).unwrap();
+        let cells = chunk_cells(board, chunk);
+        let origin = Point::new(chunk.x * CHUNK_SIZE, chunk.y * CHUNK_SIZE);
+        let Some(data) = images.get(handle).and_then(|image| image.data.as_ref()) else
         {
             continue;
+        };
+
+        let mut changed = vec![];
+        for local in cells.index2_space()
+        {
+            let offset = texel_offset(cells.width, local);
+            let mut texel: [u8; 4] = data[offset..offset + 4].try_into().unwrap();
+            write_cell(&mut texel, grid, Point::new(origin.x + local.x, origin.y + local.y));
+            if data[offset..offset + 4] != texel
+            {
+                changed.push((offset, texel));
+            }
         }
 
-        if let Some(entity) = vis.take()
+        if changed.is_empty()
         {
-            commands.entity(entity).despawn();
             continue;
         }
 
-        let world_pos = index2.as_vec2() * vis_tuning.cell_size;
-        let id = commands.spawn
-        ((
-            Tag::default(),
-            EntityIndex(size.get_index(index2).unwrap()),
-            EntityIndex2(index2),
-            Tag::sprite(&vis_tuning, &handles),
-            Transform::from_translation(world_pos.extend(Tag::layer()))
-        )).id();
-        **vis = Some(id);
+        if let Some(data) = images.get_mut(handle).and_then(|image| image.data.as_mut())
+        {
+            for (offset, texel) in changed
+            {
+                data[offset..offset + 4].copy_from_slice(&texel);
+            }
+        }
     }
 }
 
-pub fn sync_adjacency
+pub fn sync_pressed
 (
-    mut commands: Commands,
-    vis_tuning: Res<BoardVisTuning>,
+    cell_preview: Res<CellPreview>,
     grid_vis: Res<GridVis>,
-    mut entity_grid: ResMut<EntityGrid<Adjacency>>,
-    handles: Res<VisHandles>,
+    chunks: Res<CellChunks>,
+    mut images: ResMut<Assets<Image>>,
+    mut tinted: Local<Vec<Point>>,
 )
 {
-    type Tag = Adjacency;
-    
-    let size = grid_vis.grid.states.size();
-    for index2 in size.index2_space()
+    let previous = std::mem::take(&mut *tinted);
+    let mut tints = vec![];
+    if let Some(preview) = &cell_preview.preview
     {
-        let adj = grid_vis.grid.adjacency.get_by_index2(index2).unwrap();
-        let state = grid_vis.grid.states.get_by_index2(index2).unwrap();
-        let vis = &mut entity_grid.known.get_by_index2_mut(index2).unwrap();
-
-        if ( Tag::should_show(state) && *adj != 0 ) == vis.is_some()
+        let (cells, tint) = if cell_preview.pressed
         {
-            continue;
-        }
-
-        if let Some(entity) = vis.take()
-        {
-            commands.entity(entity).despawn();
-            continue;
+            (CellPreview::pressed_cells(preview, &grid_vis.grid), PRESSED)
         }
-        
-        let adj_sprite = Sprite
+        else
         {
-            image: handles.adjacency[(adj - 1) as usize].clone(),
-            custom_size: Some(vis_tuning.cell_size),
-            anchor: Anchor::BottomLeft,
-            ..default()
+            (vec![preview.pos], HOVERED)
         };
 
-        let index = grid_vis.grid.states.get_index(index2).unwrap();
-        let world_pos = index2.as_vec2() * vis_tuning.cell_size;
-        let id = commands.spawn
-        ((
-            Tag::default(),
-            EntityIndex(index),
-            EntityIndex2(index2),
-            adj_sprite,
-            Transform::from_translation(world_pos.extend(Tag::layer()))
-        )).id();
-        **vis = Some(id);
+        for pos in cells
+        {
+            tints.push((pos, tint));
+            tinted.push(pos);
+        }
     }
+
+    // cells that stay tinted are left alone so their chunk isn't uploaded again
+    tints.extend(previous.into_iter().filter(|pos| !tinted.contains(pos)).map(|pos| (pos, UNTINTED)));
+    chunks.update(&mut images, tints, |texel, _, tint| texel[TINT] = tint);
 }
 
-pub fn destroy_known
+pub fn despawn_chunks
 (
     mut commands: Commands,
-    mines: Query<Entity, With<Mine>>,
-    flags: Query<Entity, With<Flag>>,
-    covers: Query<Entity, With<Cover>>,
-    adjacency: Query<Entity, With<Adjacency>>
+    mut images: ResMut<Assets<Image>>,
+    chunks: Option<Res<CellChunks>>,
+    entities: Query<Entity, With<CellChunk>>,
 )
 {
-    for entity in mines
+    for entity in entities
     {
         commands.entity(entity).despawn();
     }
-    for entity in flags
-    {
-        commands.entity(entity).despawn();
-    }
-    for entity in covers
-    {
-        commands.entity(entity).despawn();
-    }
-    for entity in adjacency
+
+    if let Some(chunks) = chunks
     {
-        commands.entity(entity).despawn();
+        for handle in chunks.images.iter()
+        {
+            images.remove(handle);
+        }
     }
+    commands.remove_resource::<CellChunks>();
+    commands.remove_resource::<CellAnimations>();
 }
 
 pub struct GridEntitiesPlugin;
 impl Plugin for GridEntitiesPlugin
 {
-    fn build(&self, _app: &mut App)
+    fn build(&self, app: &mut App)
+    {
+        app
+            .add_plugins(Material2dPlugin::<CellMaterial>::default())
+            .add_event::<GridChanged>()
+            .add_event::<BoardEffect>()
+            .init_resource::<CellPreview>();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::animations;
+    use base::random::RandomGenerator;
+    use bevy::ecs::system::RunSystemOnce;
+    use sim::logic::Logic;
+
+    fn app(grid: Grid) -> App
     {
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<CellMaterial>()
+            .add_event::<GridChanged>()
+            .insert_resource(BoardVisTuning::default())
+            .insert_resource(GridVis{ grid })
+            .insert_resource(VisHandles
+            {
+                atlas: Handle::default(),
+                sprite_names: Default::default(),
+                sprites: Handle::default(),
+                sprite_rects: Default::default(),
+                grid_line: Handle::default(),
+                terrain_palette: Handle::default(),
+                number_colors: [LinearRgba::WHITE; 8],
+            });
+        app.world_mut().run_system_once(spawn_chunks).unwrap();
+        app
+    }
+
+    fn modified_images(app: &mut App) -> usize
+    {
+        // asset events are only flushed during an update
+        app.update();
+        let events = app.world().resource::<Events<AssetEvent<Image>>>();
+        events
+            .get_cursor()
+            .read(events)
+            .filter(|event| matches!(event, AssetEvent::Modified{ .. }))
+            .count()
+    }
+
+    #[test]
+    fn test_click_on_giant_board()
+    {
+        let mut grid = Grid::new(1000, 1000);
+        grid.first_guess = false;
+        grid.states[Point::new(501, 500)] = CellState::Mine;
+        grid.update_adjacency();
+
+        let mut app = app(grid);
+        app.update();
+        app.update();
+        assert_eq!(modified_images(&mut app), 0);
+
+        let mut grid_vis = app.world_mut().resource_mut::<GridVis>();
+        let mut logic = Logic::new();
+        let preview = logic.preview_guess(&grid_vis.grid, Point::new(500, 500));
+        let result = logic.actualize_preview(&mut grid_vis.grid, &mut RandomGenerator::new(1), &preview).unwrap();
+        let changes = GridChanged::from_result(&result);
+        assert_eq!(changes, vec![GridChanged::Revealed{ cells: vec![Point::new(500, 500)], depths: vec![0] }]);
+        app.world_mut().send_event_batch(changes);
+
+        app.world_mut().run_system_once(sync_chunks).unwrap();
+        app.world_mut().run_system_once(animations::animate_cells).unwrap();
+
+        // only the chunk holding the cell is uploaded again
+        assert_eq!(modified_images(&mut app), 1);
+        let chunks = app.world().resource::<CellChunks>();
+        let (chunk, local) = CellChunks::chunk_of(Point::new(500, 500));
+        let image = app.world().resource::<Assets<Image>>().get(&chunks.images[chunk]).unwrap();
+        let offset = texel_offset(image.width() as i32, local);
+        assert_eq!(image.data.as_ref().unwrap()[offset + STATE], CellState::Revealed.bits());
+    }
+
+    #[test]
+    fn test_first_guess_on_giant_board()
+    {
+        // two mines under the guess and a ring around it, so the opening stops at the 3x3
+        let origin = Point::new(500, 500);
+        let mut grid = Grid::new(1000, 1000);
+        for pos in grid.size().index2_space()
+        {
+            if (*pos - *origin).abs().max_element() == 2
+            {
+                grid.states[pos] = CellState::Mine;
+            }
+        }
+        grid.states[origin] = CellState::Mine;
+        grid.states[Point::new(501, 501)] = CellState::Mine;
+        grid.update_adjacency();
+
+        let mut app = app(grid);
+        app.update();
+        app.update();
+        assert_eq!(modified_images(&mut app), 0);
+
+        let mut grid_vis = app.world_mut().resource_mut::<GridVis>();
+        let mut logic = Logic::new();
+        let preview = logic.preview_guess(&grid_vis.grid, origin);
+        let result = logic.actualize_preview(&mut grid_vis.grid, &mut RandomGenerator::new(1), &preview).unwrap();
+        let changes = GridChanged::from_result(&result);
+        assert!(!changes.contains(&GridChanged::All));
+
+        // each of the two mines touches its own 3x3 where it was taken from and where it was put
+        let GridChanged::Cells(changed) = &changes[0] else
+        {
+            panic!("expected the moved mines first, got {:?}", changes[0]);
+        };
+        assert!(changed.len() <= 2 * 2 * 9);
+        let ActionResult::FirstGuess(first) = &result else
+        {
+            panic!("expected a first guess, got {result:?}");
+        };
+        assert_eq!(first.guess.revealed.len(), 9);
+        app.world_mut().send_event_batch(changes);
+
+        app.world_mut().run_system_once(sync_chunks).unwrap();
+        app.world_mut().run_system_once(animations::animate_cells).unwrap();
+
+        // the chunk with the guess, plus at most four chunks for each moved mine's neighbourhood
+        assert!(modified_images(&mut app) <= 1 + 2 * 4);
     }
 }
@@ -1,7 +1,15 @@
 
 use crate::board_vis_tuning::*;
 use crate::layers;
+use crate::grid_entities::CHUNK_SIZE;
 use crate::grid_entities::GridVis;
+use crate::grid_entities::VisHandles;
+use crate::grid_entities::chunk_cells;
+
+use base::array2::Array2;
+use base::extents::Extents;
+use base::point::Point;
+use sim::grid::CellState;
 
 use bevy::prelude::*;
 use bevy::reflect::TypePath;
@@ -20,7 +28,7 @@ pub struct GridMaterial
     
     #[texture(1)]
     #[sampler(2)]
-    base: Handle<Image>
+    pub(crate) base: Handle<Image>
 }
 
 #[derive(Component)]
@@ -48,186 +56,248 @@ impl Material2d for GridMaterial
     }
 }
 
-#[derive(Default, Debug, Clone)]
-struct GridVert
+// what is drawn along the edge between two cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeKind
 {
-    pos: Vec2,
-    uv: Vec2,
+    None,
+    Line,
+    // between the board and the land around it
+    Coast,
 }
 
-impl PartialEq for GridVert
+fn playable(states: &Array2<CellState>, pos: Point) -> bool
 {
-    fn eq(&self, other: &Self) -> bool
-    {
-        self.pos.eq(&other.pos)
-    }
-
-    fn ne(&self, other: &Self) -> bool
-    {
-        self.pos.ne(&other.pos)
-    }
+    states.get_by_index2(pos).is_some_and(|state| !state.contains(CellState::NonPlayable))
 }
 
-impl GridVert
+// cells off the board count as land
+fn edge_kind(states: &Array2<CellState>, a: Point, b: Point) -> EdgeKind
 {
-    fn from_pos(&self, pos: Vec2) -> Self
+    match (playable(states, a), playable(states, b))
     {
-        Self
-        {
-            pos,
-            uv: self.uv,
-        }
+        (false, false) => EdgeKind::None,
+        (true, true) => EdgeKind::Line,
+        _ => EdgeKind::Coast,
     }
 }
 
 #[derive(Debug, Default)]
-struct GeoBuilder
+pub struct LineGeometry
 {
-    pos: Vec<Vec2>,
-    uv: Vec<Vec2>,
-    indices: Vec<u32>,
+    pub pos: Vec<Vec2>,
+    pub uv: Vec<Vec2>,
+    pub color: Vec<LinearRgba>,
+    pub indices: Vec<u32>,
 }
 
-impl GeoBuilder
+impl LineGeometry
 {
-    fn insert_vert(&mut self, pos: Vec2, uv: Vec2) -> u32
+    fn insert_vert(&mut self, pos: Vec2, uv: Vec2, color: LinearRgba) -> u32
     {
         let index = self.pos.len() as u32;
         self.pos.push(pos);
         self.uv.push(uv);
+        self.color.push(color);
         index
     }
 
-    fn insert_index(&mut self, index: u32)
+    // the pairs are the two sides of the line at either end
+    fn insert_quad(&mut self, (start_right, start_left): (u32, u32), (end_right, end_left): (u32, u32))
     {
-        self.indices.push(index);
+        self.indices.extend([start_right, end_right, end_left]);
+        self.indices.extend([start_right, end_left, start_left]);
     }
 
-    fn drain(self) -> (Vec<Vec2>, Vec<Vec2>, Vec<u32>)
+    pub fn is_empty(&self) -> bool
     {
-        (self.pos, self.uv, self.indices)
+        self.indices.is_empty()
+    }
+
+    pub fn into_mesh(self) -> Mesh
+    {
+        let v_pos: Vec<[f32; 3]> = self.pos.iter().map(|pos| pos.extend(layers::GRID_LINE).to_array()).collect();
+        let v_color: Vec<[f32; 4]> = self.color.iter().map(|color| color.to_f32_array()).collect();
+        let v_uv: Vec<[f32; 2]> = self.uv.iter().map(|uv| uv.to_array()).collect();
+        let v_normal: Vec<[f32; 3]> = vec![Vec3::Z.to_array(); v_pos.len()];
+
+        let mut mesh = Mesh::new
+        (
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, v_pos);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, v_color);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, v_uv);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, v_normal);
+        mesh.insert_indices(bevy::render::mesh::Indices::U32(self.indices));
+        mesh
     }
 }
 
-pub fn spawn_lines
-(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<GridMaterial>>,
-    asset_server: Res<AssetServer>,
-    vis_tuning: Res<BoardVisTuning>,
-    grid_vis: Res<GridVis>,
-)
+// builds the lines between cells, one strip of quads per straight run of edges of the same kind
+pub struct GridLineBuilder<'a>
 {
-    let size = &grid_vis.grid.size();
-    let custom_material = materials.add
-    (
-        GridMaterial
+    states: &'a Array2<CellState>,
+    cell_size: Vec2,
+    tuning: &'a GridTuning,
+}
+
+impl<'a> GridLineBuilder<'a>
+{
+    pub fn new(states: &'a Array2<CellState>, cell_size: Vec2, tuning: &'a GridTuning) -> Self
+    {
+        Self
         {
-            tint: LinearRgba::BLACK.into(),
-            base: asset_server.load("textures/line.png"),
+            states,
+            cell_size,
+            tuning,
         }
-    );
+    }
 
-    let mut geo = GeoBuilder::default();
-    let half_line_width = vis_tuning.cell_size * 0.15 * 0.5;
+    // the edges below and left of the cells in [min, max), plus the far edges of the board if the area touches them
+    pub fn build(&self, min: Point, max: Point) -> LineGeometry
+    {
+        let board = self.states.size();
+        let mut geo = LineGeometry::default();
 
-    const POS_TL: Vec2 = Vec2::new(-1.0, -1.0);
-    const POS_TR: Vec2 = Vec2::new( 1.0, -1.0);
-    const POS_BL: Vec2 = Vec2::new(-1.0,  1.0);
-    const POS_BR: Vec2 = Vec2::new( 1.0,  1.0);
-    
-    const UV_TL: Vec2 = Vec2::new(0.0, 0.0);
-    const UV_TR: Vec2 = Vec2::new(1.0, 0.0);
-    const UV_BL: Vec2 = Vec2::new(0.0, 1.0);
-    const UV_BR: Vec2 = Vec2::new(1.0, 1.0);
-    
+        let top = if max.y >= board.height { board.height + 1 } else { max.y };
+        for y in min.y..top
+        {
+            self.add_runs
+            (
+                &mut geo,
+                min.x..max.x,
+                |x| edge_kind(self.states, Point::new(x, y - 1), Point::new(x, y)),
+                |x| Vec2::new(x as f32, y as f32) * self.cell_size,
+            );
+        }
+
+        let right = if max.x >= board.width { board.width + 1 } else { max.x };
+        for x in min.x..right
+        {
+            self.add_runs
+            (
+                &mut geo,
+                min.y..max.y,
+                |y| edge_kind(self.states, Point::new(x - 1, y), Point::new(x, y)),
+                |y| Vec2::new(x as f32, y as f32) * self.cell_size,
+            );
+        }
+
+        geo
+    }
+
+    // kind is of the edge starting at a corner, corner is its world position
+    fn add_runs(&self, geo: &mut LineGeometry, edges: std::ops::Range<i32>, kind: impl Fn(i32) -> EdgeKind, corner: impl Fn(i32) -> Vec2)
     {
-        // horizontal grid-aligned lines 
-        let offset = Vec2::new(0.0, half_line_width.y);
-        for y in 0..=size.height
+        let mut start = edges.start;
+        while start < edges.end
         {
-            for x in 0..size.width
+            let run_kind = kind(start);
+            let mut end = start + 1;
+            while end < edges.end && kind(end) == run_kind
             {
-                let cell_intersection = Vec2::new(x as f32, y as f32);
-                let next_cell_intersection = Vec2::new((x + 1) as f32, y as f32);
-                // UVs are rotated clockwise 90 degrees
-                let tli = geo.insert_vert(cell_intersection * vis_tuning.cell_size + offset, UV_TR);
-                let tri = geo.insert_vert(next_cell_intersection * vis_tuning.cell_size + offset, UV_BR);
-                let bli = geo.insert_vert(cell_intersection * vis_tuning.cell_size - offset, UV_TL);
-                let bri = geo.insert_vert(next_cell_intersection * vis_tuning.cell_size - offset, UV_BL);
-
-                geo.insert_index(tli);
-                geo.insert_index(tri);
-                geo.insert_index(bli);
-
-                geo.insert_index(tri);
-                geo.insert_index(bri);
-                geo.insert_index(bli);
+                end += 1;
             }
+
+            if run_kind != EdgeKind::None
+            {
+                self.add_run(geo, run_kind, start, end, &corner);
+            }
+            start = end;
         }
     }
 
+    // neighbouring edges in a run share the vertices at the corner between them
+    fn add_run(&self, geo: &mut LineGeometry, kind: EdgeKind, start: i32, end: i32, corner: impl Fn(i32) -> Vec2)
     {
-        // vertical grid-aligned lines 
-        let offset = Vec2::new(half_line_width.x, 0.0);
-        for y in 0..size.height
+        let (width, color) = match kind
         {
-            for x in 0..=size.width
+            EdgeKind::Coast => (self.tuning.coast_line_width, self.tuning.coast_color),
+            _ => (self.tuning.world_line_width, self.tuning.line_color),
+        };
+        let half_width = width * 0.5;
+        let color = LinearRgba::from(color);
+        let along = (corner(start + 1) - corner(start)).normalize();
+        let side = along.perp() * half_width;
+        let uv_right = 0.5 - self.tuning.uv_width;
+        let uv_left = 0.5 + self.tuning.uv_width;
+
+        let mut previous = None;
+        for i in start..=end
+        {
+            // the ends reach half a width past the corner so crossing runs close up
+            let mut pos = corner(i);
+            if i == start
+            {
+                pos -= along * half_width;
+            }
+            else if i == end
             {
-                let cell_intersection = Vec2::new(x as f32, y as f32);
-                let next_cell_intersection = Vec2::new(x as f32, (y + 1) as f32);
-                let tli = geo.insert_vert(cell_intersection * vis_tuning.cell_size - offset, UV_TL);
-                let tri = geo.insert_vert(cell_intersection * vis_tuning.cell_size + offset, UV_TR);
-                let bli = geo.insert_vert(next_cell_intersection * vis_tuning.cell_size - offset, UV_BL);
-                let bri = geo.insert_vert(next_cell_intersection * vis_tuning.cell_size + offset, UV_BR);
-
-                geo.insert_index(tli);
-                geo.insert_index(tri);
-                geo.insert_index(bli);
-
-                geo.insert_index(tri);
-                geo.insert_index(bri);
-                geo.insert_index(bli);
+                pos += along * half_width;
             }
+
+            let v = (i & 1) as f32;
+            let right = geo.insert_vert(pos - side, Vec2::new(uv_right, v), color);
+            let left = geo.insert_vert(pos + side, Vec2::new(uv_left, v), color);
+            if let Some(previous) = previous
+            {
+                geo.insert_quad(previous, (right, left));
+            }
+            previous = Some((right, left));
         }
     }
+}
 
-    let mut v_pos = vec![];
-    let mut v_color = vec![];
-    let mut v_uv = vec![];
-    let mut v_normal = vec![];
-
-    let (poses, uvs, indices) = geo.drain();
-    const NORMAL: Vec3 = Vec3::new(0.0, 0.0, 0.0);
-    for (pos, uv) in poses.iter().zip(uvs)
-    {
-        v_pos.push(pos.extend(layers::GRID_LINE).to_array());
-        v_color.push(LinearRgba::BLACK.to_f32_array());
-        v_uv.push(uv.to_array());
-        v_normal.push(NORMAL.to_array());
-    }
+// one mesh per cell chunk so offscreen lines are culled
+pub fn spawn_lines
+(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+    vis_tuning: Res<BoardVisTuning>,
+    grid_vis: Res<GridVis>,
+    handles: Res<VisHandles>,
+)
+{
+    let board = grid_vis.grid.size();
+    // line colours come from the vertices
+    let material = materials.add
+    (
+        GridMaterial
+        {
+            tint: LinearRgba::WHITE,
+            base: handles.grid_line.clone(),
+        }
+    );
 
-    let mut mesh = Mesh::new
+    let builder = GridLineBuilder::new(&grid_vis.grid.states, vis_tuning.cell_size, &vis_tuning.grid);
+    let chunk_extents = Extents::new
     (
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::RENDER_WORLD,
+        (board.width + CHUNK_SIZE - 1) / CHUNK_SIZE,
+        (board.height + CHUNK_SIZE - 1) / CHUNK_SIZE,
     );
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, v_pos);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, v_color);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, v_uv);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, v_normal);
-    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
-
-    let mesh_id = meshes.add(mesh);
-
-    commands
-        .spawn
-        ((
-            GridLines,
-            Mesh2d(mesh_id.into()),
-            MeshMaterial2d(custom_material.into())
-        ));
+    for chunk in chunk_extents.index2_space()
+    {
+        let cells = chunk_cells(board, chunk);
+        let min = Point::new(chunk.x * CHUNK_SIZE, chunk.y * CHUNK_SIZE);
+        let max = Point::new(min.x + cells.width, min.y + cells.height);
+
+        let geo = builder.build(min, max);
+        if geo.is_empty()
+        {
+            continue;
+        }
+
+        commands
+            .spawn
+            ((
+                GridLines,
+                Mesh2d(meshes.add(geo.into_mesh())),
+                MeshMaterial2d(material.clone()),
+            ));
+    }
 }
 
 pub fn despawn_lines
@@ -253,3 +323,95 @@ impl Plugin for GridLinesPlugin
             ;
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn board(rows: &[&str]) -> Array2<CellState>
+    {
+        // rows top down, '#' is land like in board_format
+        let mut states = Array2::new(rows[0].len() as i32, rows.len() as i32);
+        for (row, line) in rows.iter().rev().enumerate()
+        {
+            for (x, c) in line.chars().enumerate()
+            {
+                if c == '#'
+                {
+                    states[Point::new(x as i32, row as i32)] = CellState::NonPlayable;
+                }
+            }
+        }
+        states
+    }
+
+    fn build(states: &Array2<CellState>, tuning: &GridTuning) -> LineGeometry
+    {
+        GridLineBuilder::new(states, Vec2::ONE, tuning).build(Point::new(0, 0), Point::new(states.width(), states.height()))
+    }
+
+    #[test]
+    fn test_shared_vertices()
+    {
+        let tuning = GridTuning::default();
+        let geo = build(&board(&["..", ".."]), &tuning);
+
+        // three lines each way, each one run of two edges with three corners
+        assert_eq!(geo.pos.len(), 6 * 2 * 3);
+        assert_eq!(geo.indices.len(), 6 * 2 * 6);
+        let coast = geo.color.iter().filter(|color| **color == LinearRgba::from(tuning.coast_color)).count();
+        assert_eq!(coast, 4 * 2 * 3);
+    }
+
+    #[test]
+    fn test_skips_land()
+    {
+        let tuning = GridTuning::default();
+        assert!(build(&board(&["##", "##"]), &tuning).is_empty());
+
+        // nothing along the top or between the two land cells
+        let geo = build(&board(&["##", ".."]), &tuning);
+        assert_eq!(geo.pos.len(), 6 + 6 + 4 + 4 + 4);
+
+        // the island in the middle is outlined by coast and splits the lines around it
+        let geo = build(&board(&["...", ".#.", "..."]), &tuning);
+        let coast = geo.color.iter().filter(|color| **color == LinearRgba::from(tuning.coast_color)).count();
+        let line = geo.color.iter().filter(|color| **color == LinearRgba::from(tuning.line_color)).count();
+        assert_eq!(coast, 2 * (8 + 4 + 4 + 8));
+        assert_eq!(line, 2 * (8 + 8));
+    }
+
+    #[test]
+    fn test_line_width()
+    {
+        let tuning = GridTuning
+        {
+            world_line_width: 0.1,
+            coast_line_width: 0.3,
+            ..default()
+        };
+        let geo = build(&board(&["...", "..."]), &tuning);
+        for pair in geo.pos.chunks(2).zip(geo.color.chunks(2))
+        {
+            let ([right, left], [color, _]) = pair else { unreachable!() };
+            let width = if *color == LinearRgba::from(tuning.coast_color) { 0.3 } else { 0.1 };
+            assert!((right.distance(*left) - width).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_chunks_cover_board()
+    {
+        let tuning = GridTuning::default();
+        let states = board(&["..#..", ".##..", ".....", "#...."]);
+        let whole = build(&states, &tuning);
+
+        let builder = GridLineBuilder::new(&states, Vec2::ONE, &tuning);
+        let quads: usize = [(0, 0, 2, 3), (2, 0, 5, 3), (0, 3, 2, 4), (2, 3, 5, 4)]
+            .into_iter()
+            .map(|(x0, y0, x1, y1)| builder.build(Point::new(x0, y0), Point::new(x1, y1)).indices.len() / 6)
+            .sum();
+        assert_eq!(quads, whole.indices.len() / 6);
+    }
+}
@@ -1,15 +1,17 @@
 
+pub mod animations;
 pub mod board_vis_tuning;
 pub mod grid_entities;
 pub mod grid_lines;
+pub mod minimap;
 pub mod terrain_grid;
 pub mod terrain_vis;
+pub mod theme;
 mod layers;
 
 use board_vis_tuning::*;
 
-use base::tuning::Tuning;
-use bevyx::ron::RonAssetPlugin;
+use bevyx::tuning::TuningPlugin;
 
 use bevy::prelude::*;
 
@@ -18,15 +20,6 @@ pub fn hello_vis()
     println!("Hello, vis!");
 }
 
-fn startup
-(
-    mut commands: Commands,
-)
-{
-    BoardVisTuning::default().save();
-    commands.insert_resource(BoardVisTuning::load());
-}
-
 pub struct GameVisPlugin;
 
 impl Plugin for GameVisPlugin
@@ -34,7 +27,8 @@ impl Plugin for GameVisPlugin
     fn build(&self, app: &mut App)
     {
         app
-            .add_plugins(RonAssetPlugin::<BoardVisTuning>::default())
-            .add_systems(Startup, startup);
+            .add_plugins(TuningPlugin::<BoardVisTuning>::default())
+            .add_plugins(TuningPlugin::<TerrainTuning>::default())
+            .add_plugins(theme::ThemePlugin);
     }
 }
@@ -0,0 +1,242 @@
+use crate::grid_entities::GridChanged;
+use crate::grid_entities::GridVis;
+use crate::grid_entities::VisHandles;
+use crate::terrain_vis;
+
+use base::array2::Array2;
+use base::extents::Extents;
+use base::point::Point;
+use sim::grid::CellState;
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::TextureFormat;
+
+// longest side of the texture, bigger boards fold several cells into one pixel
+const MAX_SIZE: i32 = 256;
+
+// the top of the default terrain palette, until the theme's palette has loaded
+const LAND: [u8; 4] = [97, 218, 0, 255];
+const COVERED: [u8; 4] = [96, 108, 96, 255];
+const REVEALED: [u8; 4] = [214, 198, 160, 255];
+const FLAGGED: [u8; 4] = [220, 48, 48, 255];
+
+#[derive(Resource, Debug)]
+pub struct Minimap
+{
+    pub image: Handle<Image>,
+    // cells along each side of one pixel
+    pub cells_per_pixel: i32,
+    // the states the texture was last written from
+    known: Array2<CellState>,
+    land: [u8; 4],
+}
+
+impl Minimap
+{
+    pub fn pixel_size(&self) -> Extents
+    {
+        pixel_size(self.known.size(), self.cells_per_pixel)
+    }
+}
+
+fn pixel_size(cells: Extents, cells_per_pixel: i32) -> Extents
+{
+    Extents::new
+    (
+        (cells.width + cells_per_pixel - 1) / cells_per_pixel,
+        (cells.height + cells_per_pixel - 1) / cells_per_pixel,
+    )
+}
+
+// non playable cells are land, drawn as the terrain shader draws fully raised ground
+fn land_color(handles: &VisHandles, images: &Assets<Image>) -> Option<[u8; 4]>
+{
+    let palette = images.get(&handles.terrain_palette)?;
+    let color = palette.get_color_at(palette.width() - 1, 0).ok()?;
+    Some(color.to_srgba().to_u8_array())
+}
+
+fn cell_color(state: CellState, land: [u8; 4]) -> [u8; 4]
+{
+    if state.intersects(CellState::NonPlayable)
+    {
+        land
+    }
+    else if state.intersects(CellState::Flag)
+    {
+        FLAGGED
+    }
+    else if state.intersects(CellState::Revealed)
+    {
+        REVEALED
+    }
+    else
+    {
+        COVERED
+    }
+}
+
+// the average of every cell folded into the pixel
+fn pixel_color(states: &Array2<CellState>, pixel: Point, cells_per_pixel: i32, land: [u8; 4]) -> [u8; 4]
+{
+    let mut sum = [0u32; 4];
+    let mut count = 0;
+    for y in 0..cells_per_pixel
+    {
+        for x in 0..cells_per_pixel
+        {
+            let pos = Point::new(pixel.x * cells_per_pixel + x, pixel.y * cells_per_pixel + y);
+            if let Some(&state) = states.get_by_index2(pos)
+            {
+                for (acc, channel) in sum.iter_mut().zip(cell_color(state, land))
+                {
+                    *acc += channel as u32;
+                }
+                count += 1;
+            }
+        }
+    }
+    sum.map(|channel| (channel / count.max(1)) as u8)
+}
+
+// rows run top down in the image, cell rows run bottom up in the world
+fn image_pos(size: Extents, pixel: Point) -> Point
+{
+    Point::new(pixel.x, size.height - 1 - pixel.y)
+}
+
+fn write_pixel(data: &mut [u8], size: Extents, pixel: Point, color: [u8; 4])
+{
+    let pos = image_pos(size, pixel);
+    let offset = (pos.y * size.width + pos.x) as usize * 4;
+    data[offset..offset + 4].copy_from_slice(&color);
+}
+
+pub fn startup
+(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    grid_vis: Res<GridVis>,
+    handles: Res<VisHandles>,
+)
+{
+    let states = grid_vis.grid.states.clone();
+    let cells_per_pixel = ((states.width().max(states.height()) + MAX_SIZE - 1) / MAX_SIZE).max(1);
+    let size = pixel_size(states.size(), cells_per_pixel);
+    let land = land_color(&handles, &images).unwrap_or(LAND);
+
+    let mut pixels = Array2::<[u8; 4]>::from_size(size);
+    for pixel in size.index2_space()
+    {
+        pixels[image_pos(size, pixel)] = pixel_color(&states, pixel, cells_per_pixel, land);
+    }
+
+    let mut image = terrain_vis::array2_image
+    (
+        &pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+        |color| *color
+    );
+    image.sampler = bevy::image::ImageSampler::nearest();
+
+    commands.insert_resource(Minimap
+    {
+        image: images.add(image),
+        cells_per_pixel,
+        known: states,
+        land,
+    });
+}
+
+// only pixels over cells that changed since the last sync are recomputed
+pub fn sync
+(
+    mut changes: EventReader<GridChanged>,
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+    grid_vis: Res<GridVis>,
+    handles: Res<VisHandles>,
+)
+{
+    let states = &grid_vis.grid.states;
+    if states.size() != minimap.known.size()
+    {
+        changes.clear();
+        return;
+    }
+
+    let cells_per_pixel = minimap.cells_per_pixel;
+    let size = minimap.pixel_size();
+    let mut dirty = vec![];
+    // the palette can load after the board, or change with the theme
+    if let Some(land) = land_color(&handles, &images) && land != minimap.land
+    {
+        minimap.land = land;
+        dirty.extend(size.index_space());
+    }
+    let land = minimap.land;
+    let mut mark = |known: &mut CellState, pos: Point|
+    {
+        if *known != states[pos]
+        {
+            *known = states[pos];
+            dirty.push(size.get_index(Point::new(pos.x / cells_per_pixel, pos.y / cells_per_pixel)).unwrap());
+        }
+    };
+
+    for change in changes.read()
+    {
+        match change
+        {
+            GridChanged::Cells(cells) | GridChanged::Revealed{ cells, .. } =>
+            {
+                for pos in cells
+                {
+                    if let Some(known) = minimap.known.get_by_index2_mut(*pos)
+                    {
+                        mark(known, *pos);
+                    }
+                }
+            }
+            GridChanged::All =>
+            {
+                for (index, known) in minimap.known.iter_mut().enumerate()
+                {
+                    mark(known, states.get_index2(index).unwrap());
+                }
+            }
+        }
+    }
+
+    if dirty.is_empty()
+    {
+        return;
+    }
+    dirty.sort();
+    dirty.dedup();
+
+    let Some(data) = images.get_mut(&minimap.image).and_then(|image| image.data.as_mut()) else
+    {
+        return;
+    };
+    for pixel in dirty.into_iter().filter_map(|index| size.get_index2(index))
+    {
+        write_pixel(data, size, pixel, pixel_color(states, pixel, cells_per_pixel, land));
+    }
+}
+
+pub fn shutdown
+(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    minimap: Option<Res<Minimap>>,
+)
+{
+    if let Some(minimap) = minimap
+    {
+        images.remove(&minimap.image);
+    }
+    commands.remove_resource::<Minimap>();
+}
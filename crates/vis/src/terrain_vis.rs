@@ -2,6 +2,7 @@
 use base::array2::Array2;
 use base::point::Point;
 use crate::board_vis_tuning::*;
+use crate::grid_entities::VisHandles;
 use crate::layers;
 use crate::terrain_grid::CellType;
 use crate::terrain_grid::TerrainGrid;
@@ -68,11 +69,103 @@ fn guassian_blur(data: &mut Array2<f32>, passes: u32)
     blur(data, &[0.06136, 0.24477, 0.38774, 0.24477, 0.06136], passes)
 }
 
+// one texel per element in array order, so the first row is the top of the image
+pub fn array2_image<T>(data: &Array2<T>, format: TextureFormat, asset_usage: RenderAssetUsages, texel: impl Fn(&T) -> [u8; 4]) -> Image
+{
+    let size = data.size();
+    let mut bytes = Vec::with_capacity(size.num_elements() * 4);
+    for value in data.iter()
+    {
+        bytes.extend_from_slice(&texel(value));
+    }
+    Image::new
+    (
+        Extent3d{
+            width: size.width as u32,
+            height: size.height as u32,
+            depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        bytes,
+        format,
+        asset_usage
+    )
+}
+
+// the derive emits field checks it never calls, which trips dead_code
+#[allow(dead_code)]
+mod uniform
+{
+    use bevy::prelude::*;
+    use bevy::render::render_resource::ShaderType;
+
+    // laid out to match the uniform in terrain.wgsl
+    #[derive(ShaderType, Debug, Clone)]
+    pub struct TerrainUniform
+    {
+        pub(super) tint: LinearRgba,
+        pub(super) shallow_color: LinearRgba,
+        pub(super) deep_color: LinearRgba,
+        pub(super) foam_color: LinearRgba,
+        // in cells
+        pub(super) board_size: Vec2,
+        pub(super) wave_speed: Vec2,
+        pub(super) wave_scale: f32,
+        pub(super) wave_strength: f32,
+        pub(super) foam_width: f32,
+        pub(super) foam_speed: f32,
+        pub(super) foam_sharpness: f32,
+        pub(super) shore_level: f32,
+        // seconds
+        pub(super) time: f32,
+    }
+}
+
+pub use uniform::TerrainUniform;
+
+impl TerrainUniform
+{
+    fn new(board_size: Vec2, tuning: &TerrainTuning) -> Self
+    {
+        let mut uniform = Self
+        {
+            tint: LinearRgba::WHITE,
+            shallow_color: default(),
+            deep_color: default(),
+            foam_color: default(),
+            board_size,
+            wave_speed: default(),
+            wave_scale: default(),
+            wave_strength: default(),
+            foam_width: default(),
+            foam_speed: default(),
+            foam_sharpness: default(),
+            shore_level: default(),
+            time: 0.0,
+        };
+        uniform.apply(tuning);
+        uniform
+    }
+
+    fn apply(&mut self, tuning: &TerrainTuning)
+    {
+        self.shallow_color = tuning.shallow_color.into();
+        self.deep_color = tuning.deep_color.into();
+        self.foam_color = tuning.foam_color.into();
+        self.wave_speed = tuning.wave_speed;
+        self.wave_scale = tuning.wave_scale;
+        self.wave_strength = tuning.wave_strength;
+        self.foam_width = tuning.foam_width;
+        self.foam_speed = tuning.foam_speed;
+        self.foam_sharpness = tuning.foam_sharpness;
+        self.shore_level = tuning.shore_level;
+    }
+}
+
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct TerrainMaterial
 {
     #[uniform(0)]
-    color: LinearRgba,
+    terrain: TerrainUniform,
     #[texture(1, sample_type="float")]
     #[sampler(2, sampler_type="filtering")]
     elevation: Handle<Image>,
@@ -80,7 +173,7 @@ pub struct TerrainMaterial
     // should be 1d, but bevy can't load png as 1d
     #[texture(3, sample_type="float", dimension="2d")]
     #[sampler(4, sampler_type="filtering")]
-    color_palette: Handle<Image>,
+    pub(crate) color_palette: Handle<Image>,
 }
 
 impl Material2d for TerrainMaterial
@@ -101,6 +194,7 @@ impl Material2d for TerrainMaterial
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn startup
 (
     mut commands: Commands,
@@ -109,7 +203,8 @@ pub fn startup
     mut images: ResMut<Assets<Image>>,
     terrain_grid: Res<TerrainGrid>,
     vis_tuning: Res<BoardVisTuning>,
-    asset_server: Res<AssetServer>,
+    terrain_tuning: Res<TerrainTuning>,
+    handles: Res<VisHandles>,
 )
 {
     let size = terrain_grid.size();
@@ -133,31 +228,20 @@ pub fn startup
 
         guassian_blur(&mut height_map, 2);
 
-        let elevation_size = height_map.size();
-        let mut elevation: Vec<u8> = vec![];
-        elevation.resize(elevation_size.width as usize * elevation_size.height as usize * size_of::<u32>(), 0);
-        let elevation_slice = bytemuck::cast_slice_mut::<u8, u32>(&mut elevation);
-        for i in 0..elevation_size.num_elements()
-        {
-            elevation_slice[i] = (height_map[i].clamp(0.0, 1.0) * 255.0) as u32;
-        }
-        let mut elevation_image = Image::new
+        let mut elevation_image = array2_image
         (
-            Extent3d{
-                width: elevation_size.width as u32,
-                height: elevation_size.height as u32,
-                depth_or_array_layers: 1 },
-            TextureDimension::D2,
-            elevation,
+            &height_map,
             TextureFormat::Rgba8Unorm,
-            RenderAssetUsages::RENDER_WORLD
+            RenderAssetUsages::RENDER_WORLD,
+            |height| [(height.clamp(0.0, 1.0) * 255.0) as u8, 0, 0, 0]
         );
         elevation_image.sampler = bevy::image::ImageSampler::Descriptor
         (
             bevy::image::ImageSamplerDescriptor {
                 label: Some("elevation".to_owned()),
-                mag_filter: bevy::image::ImageFilterMode::Nearest,
-                min_filter: bevy::image::ImageFilterMode::Nearest,
+                // smooth, the water shades by depth and slope
+                mag_filter: bevy::image::ImageFilterMode::Linear,
+                min_filter: bevy::image::ImageFilterMode::Linear,
                 mipmap_filter: bevy::image::ImageFilterMode::Nearest,
                 ..Default::default()
             }
@@ -170,9 +254,9 @@ pub fn startup
     (
         TerrainMaterial
         {
-            color: Color::WHITE.into(),
+            terrain: TerrainUniform::new(Vec2::new(size.width as f32, size.height as f32), &terrain_tuning),
             elevation: elevation_handle,
-            color_palette: asset_server.load("textures/palette.png"),
+            color_palette: handles.terrain_palette.clone(),
         }
     );
 
@@ -235,6 +319,24 @@ pub fn shutdown
     }
 }
 
+// keeps the waves moving and picks up edits to the tuning
+pub fn update_material
+(
+    time: Res<Time>,
+    terrain_tuning: Res<TerrainTuning>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+)
+{
+    for (_, material) in materials.iter_mut()
+    {
+        material.terrain.time = time.elapsed_secs_wrapped();
+        if terrain_tuning.is_changed()
+        {
+            material.terrain.apply(&terrain_tuning);
+        }
+    }
+}
+
 pub struct TerrainVisPlugin;
 
 impl Plugin for TerrainVisPlugin
@@ -243,6 +345,7 @@ impl Plugin for TerrainVisPlugin
     {
         app
             .add_plugins(Material2dPlugin::<TerrainMaterial>::default())
+            .add_systems(Update, update_material.run_if(resource_exists::<TerrainTuning>))
             ;
     }
 }
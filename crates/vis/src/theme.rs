@@ -0,0 +1,275 @@
+use crate::grid_entities::CellMaterial;
+use crate::grid_entities::VisHandles;
+use crate::grid_lines::GridMaterial;
+use crate::terrain_vis::TerrainMaterial;
+
+use base::assets::ASSETS_FOLDER;
+use base::tuning::Tuning;
+use bevyx::atlas::Atlas;
+use bevyx::atlas::AtlasPlugin;
+use bevyx::ron::RonAssetPlugin;
+use bevyx::watch::AssetWatcher;
+
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+const THEMES_FOLDER: &str = "themes";
+const DEFAULT_THEME: &str = "themes/default.ron";
+
+// every sprite and colour the board is drawn with, paths are asset paths
+#[derive(serde::Deserialize, serde::Serialize, Asset, TypePath, Debug, Clone)]
+pub struct BoardTheme
+{
+    pub name: String,
+    // packed by the assets tool, the cell sprites are names in it
+    pub atlas: PathBuf,
+    pub cover: String,
+    pub flag: String,
+    pub mine: String,
+    pub numbers: [String; 8],
+    pub grid_line: PathBuf,
+    pub terrain_palette: PathBuf,
+    // multiplied over the number sprites
+    pub number_colors: [Srgba; 8],
+}
+
+impl Default for BoardTheme
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            name: "Default".to_owned(),
+            atlas: "atlases/cells.atlas.ron".into(),
+            cover: "cover".to_owned(),
+            flag: "flag".to_owned(),
+            mine: "mine".to_owned(),
+            numbers: std::array::from_fn(|index| (index + 1).to_string()),
+            grid_line: "textures/line.png".into(),
+            terrain_palette: "textures/palette.png".into(),
+            number_colors: [Srgba::WHITE; 8],
+        }
+    }
+}
+
+impl BoardTheme
+{
+    pub fn files(&self) -> impl Iterator<Item = &Path>
+    {
+        [&self.atlas, &self.grid_line, &self.terrain_palette]
+            .into_iter()
+            .map(PathBuf::as_path)
+    }
+}
+
+// the theme picked in settings
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct ThemeSelection
+{
+    pub theme: PathBuf,
+}
+
+impl Default for ThemeSelection
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            theme: DEFAULT_THEME.into(),
+        }
+    }
+}
+
+impl Tuning for ThemeSelection
+{
+    fn path() -> &'static Path
+    {
+        Path::new("tuning/theme.ron")
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct Themes
+{
+    // asset paths of every theme in the themes folder
+    pub available: Vec<PathBuf>,
+    pub current_path: PathBuf,
+    pub current: Handle<BoardTheme>,
+}
+
+impl Themes
+{
+    pub fn select(&mut self, path: &Path, asset_server: &AssetServer)
+    {
+        self.current_path = path.to_path_buf();
+        self.current = asset_server.load(path.to_path_buf());
+    }
+
+    pub fn selection(&self) -> ThemeSelection
+    {
+        ThemeSelection
+        {
+            theme: self.current_path.clone(),
+        }
+    }
+}
+
+fn find_themes() -> Vec<PathBuf>
+{
+    let Ok(entries) = std::fs::read_dir(Path::new(ASSETS_FOLDER).join(THEMES_FOLDER)) else
+    {
+        return vec![];
+    };
+
+    let mut themes: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .filter_map(|path| path.file_name().map(|name| Path::new(THEMES_FOLDER).join(name)))
+        .collect();
+    themes.sort();
+    themes
+}
+
+pub fn startup
+(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+)
+{
+    let available = find_themes();
+    let mut selected = ThemeSelection::load().theme;
+    if !available.contains(&selected)
+    {
+        selected = DEFAULT_THEME.into();
+    }
+
+    commands.insert_resource(Themes
+    {
+        available,
+        current: asset_server.load(selected.clone()),
+        current_path: selected,
+    });
+}
+
+// polls the files of the current theme and reloads the ones that changed, so themes can be edited while playing
+pub fn watch
+(
+    time: Res<Time>,
+    mut watcher: ResMut<AssetWatcher<BoardTheme>>,
+    themes: Res<Themes>,
+    theme_assets: Res<Assets<BoardTheme>>,
+    atlases: Res<Assets<Atlas>>,
+    asset_server: Res<AssetServer>,
+)
+{
+    watcher.poll(&time, &asset_server, ||
+    {
+        let mut files = vec![themes.current_path.clone()];
+        if let Some(theme) = theme_assets.get(&themes.current)
+        {
+            files.extend(theme.files().map(Path::to_path_buf));
+            // the atlas image isn't reloaded along with its manifest
+            let atlas_image = asset_server
+                .get_handle::<Atlas>(theme.atlas.as_path())
+                .and_then(|atlas| atlases.get(&atlas))
+                .and_then(|atlas| asset_server.get_path(&atlas.image));
+            if let Some(path) = atlas_image
+            {
+                files.push(path.path().to_path_buf());
+            }
+        }
+        files
+    });
+}
+
+// points every board material at the current theme, again whenever it or one of its images reloads
+#[allow(clippy::too_many_arguments)]
+pub fn apply
+(
+    mut theme_events: EventReader<AssetEvent<BoardTheme>>,
+    mut atlas_events: EventReader<AssetEvent<Atlas>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    themes: Res<Themes>,
+    theme_assets: Res<Assets<BoardTheme>>,
+    atlases: Res<Assets<Atlas>>,
+    asset_server: Res<AssetServer>,
+    mut handles: ResMut<VisHandles>,
+    mut cell_materials: ResMut<Assets<CellMaterial>>,
+    mut grid_materials: ResMut<Assets<GridMaterial>>,
+    mut terrain_materials: ResMut<Assets<TerrainMaterial>>,
+)
+{
+    let current = themes.current.id();
+    // folds rather than any() so every event is read
+    let theme_changed = themes.is_changed() | theme_events.read().fold(false, |changed, event|
+    {
+        changed | event.is_loaded_with_dependencies(current) | event.is_modified(current)
+    });
+    let atlas_changed = atlas_events.read().fold(false, |changed, event|
+    {
+        changed | matches!(event, AssetEvent::LoadedWithDependencies{ id } | AssetEvent::Modified{ id } if handles.is_atlas(*id))
+    });
+    let images_changed = image_events.read().fold(false, |changed, event|
+    {
+        changed | matches!(event, AssetEvent::Modified{ id } if handles.contains(*id))
+    });
+    if !theme_changed && !atlas_changed && !images_changed
+    {
+        return;
+    }
+
+    if theme_changed
+    {
+        let Some(theme) = theme_assets.get(current) else
+        {
+            return;
+        };
+        *handles = VisHandles::new(theme, &asset_server);
+    }
+    if theme_changed || atlas_changed
+    {
+        handles.resolve(&atlases);
+    }
+
+    // materials don't pick up reloaded images by themselves
+    for (_, material) in cell_materials.iter_mut()
+    {
+        material.apply_handles(&handles);
+    }
+    for (_, material) in grid_materials.iter_mut()
+    {
+        material.base = handles.grid_line.clone();
+    }
+    for (_, material) in terrain_materials.iter_mut()
+    {
+        material.color_palette = handles.terrain_palette.clone();
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin
+{
+    fn build(&self, app: &mut App)
+    {
+        app
+            .add_plugins(RonAssetPlugin::<BoardTheme>::default())
+            .add_plugins(AtlasPlugin)
+            .init_resource::<AssetWatcher<BoardTheme>>()
+            .add_systems(Startup, startup)
+            .add_systems
+            (
+                Update,
+                (
+                    watch,
+                    apply.run_if(resource_exists::<VisHandles>),
+                )
+                .chain()
+                .run_if(resource_exists::<Themes>)
+            );
+    }
+}